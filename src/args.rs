@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+
+use macroquad::prelude::Vec2;
+use verlet::{Material, DEFAULT_RADIUS};
+
+const USAGE: &str = "usage: verlet-rs [--substeps N] [--gravity X,Y] [--damping K] [--drag K] \
+[--friction STATIC,KINETIC] [--restitution E] [--radius R] [--blob-pressure P] \
+[--blob-stiffness S] [--scene PATH] [--width PX] [--height PX] [--seed N] [--threads N]";
+
+/// Command-line options of the interactive demo.
+#[derive(Clone, Debug)]
+pub struct Args {
+    pub substeps: u32,
+    pub gravity: Vec2,
+    /// Linear damping rate per second.
+    pub damping: f32,
+    /// Quadratic air drag coefficient.
+    pub drag: f32,
+    /// Surface of the spawned particles and of the walls.
+    pub material: Material,
+    /// Bounciness of every wall, the integrator's own if not given.
+    pub restitution: Option<f32>,
+    /// Radius of the particles spawned with the mouse.
+    pub radius: f32,
+    /// Pressure of the soft bodies spawned with B, see `verlet::SoftBody`.
+    pub blob_pressure: f32,
+    /// Stiffness of the soft bodies' outline.
+    pub blob_stiffness: f32,
+    pub scene: Option<PathBuf>,
+    pub width: i32,
+    pub height: i32,
+    /// Random seed for spawning, taken from the clock if not given.
+    pub seed: Option<u64>,
+    /// Solver threads, rayon's default if not given.
+    pub threads: Option<usize>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            substeps: 8,
+            gravity: Vec2::new(0.0, 1000.0),
+            damping: 0.0,
+            drag: 0.0,
+            material: Material::FRICTIONLESS,
+            restitution: None,
+            radius: DEFAULT_RADIUS,
+            blob_pressure: 2000.0,
+            blob_stiffness: 1.0,
+            scene: None,
+            // Same as macroquad's default window
+            width: 800,
+            height: 600,
+            seed: None,
+            threads: None,
+        }
+    }
+}
+
+impl Args {
+    /// Parse the process arguments, exiting with a usage message if they are invalid.
+    pub fn from_env() -> Args {
+        match Args::parse(std::env::args().skip(1)) {
+            Ok(args) => args,
+            Err(error) => {
+                eprintln!("{error}\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            let value = args.next().ok_or(format!("missing value for {arg}"))?;
+            match arg.as_str() {
+                "--substeps" => parsed.substeps = parse(&value)?,
+                "--gravity" => {
+                    let (x, y) = parse_pair(&arg, &value)?;
+                    parsed.gravity = Vec2::new(x, y);
+                }
+                "--damping" => parsed.damping = parse(&value)?,
+                "--drag" => parsed.drag = parse(&value)?,
+                "--friction" => {
+                    let (static_friction, kinetic_friction) = parse_pair(&arg, &value)?;
+                    parsed.material = Material::new(static_friction, kinetic_friction);
+                }
+                "--restitution" => parsed.restitution = Some(parse(&value)?),
+                "--radius" => parsed.radius = parse_positive(&arg, &value)?,
+                "--blob-pressure" => parsed.blob_pressure = parse(&value)?,
+                "--blob-stiffness" => parsed.blob_stiffness = parse(&value)?,
+                "--scene" => parsed.scene = Some(PathBuf::from(value)),
+                "--width" => parsed.width = parse_positive(&arg, &value)?,
+                "--height" => parsed.height = parse_positive(&arg, &value)?,
+                "--seed" => parsed.seed = Some(parse(&value)?),
+                "--threads" => parsed.threads = Some(parse(&value)?),
+                _ => return Err(format!("unknown option {arg}")),
+            }
+        }
+        // Particles bounce off each other like off the walls
+        parsed.material.restitution = parsed.restitution;
+        Ok(parsed)
+    }
+}
+
+/// Two comma-separated numbers.
+fn parse_pair(arg: &str, value: &str) -> Result<(f32, f32), String> {
+    let (a, b) = value.split_once(',').ok_or(format!(
+        "expected two comma-separated values for {arg}, got {value}"
+    ))?;
+    Ok((parse(a)?, parse(b)?))
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid value {value}"))
+}
+
+/// A number greater than 0, for sizes.
+fn parse_positive<T: std::str::FromStr + PartialOrd + Default>(
+    arg: &str,
+    value: &str,
+) -> Result<T, String> {
+    let parsed: T = parse(value)?;
+    // Also rejects NaN
+    if parsed > T::default() {
+        Ok(parsed)
+    } else {
+        Err(format!("{arg} must be greater than 0, got {value}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn defaults_without_arguments() {
+        let args = parse_args(&[]).unwrap();
+        assert_eq!(args.substeps, 8);
+        assert_eq!(args.radius, DEFAULT_RADIUS);
+        assert_eq!((args.width, args.height), (800, 600));
+        assert_eq!(args.seed, None);
+    }
+
+    #[test]
+    fn parses_every_kind_of_value() {
+        let args = parse_args(&[
+            "--gravity",
+            "0,500",
+            "--friction",
+            "0.6, 0.4",
+            "--restitution",
+            "0.8",
+            "--radius",
+            "2.5",
+            "--width",
+            "1280",
+            "--seed",
+            "42",
+            "--scene",
+            "quicksave.ron",
+        ])
+        .unwrap();
+        assert_eq!(args.gravity, Vec2::new(0.0, 500.0));
+        assert_eq!(args.material.static_friction, 0.6);
+        assert_eq!(args.material.kinetic_friction, 0.4);
+        // Shared with the particles
+        assert_eq!(args.restitution, Some(0.8));
+        assert_eq!(args.material.restitution, Some(0.8));
+        assert_eq!(args.radius, 2.5);
+        assert_eq!(args.width, 1280);
+        assert_eq!(args.seed, Some(42));
+        assert_eq!(args.scene, Some(PathBuf::from("quicksave.ron")));
+    }
+
+    #[test]
+    fn sizes_must_be_positive() {
+        for args in [
+            ["--radius", "0"],
+            ["--radius", "-1"],
+            ["--radius", "NaN"],
+            ["--width", "0"],
+            ["--height", "-600"],
+        ] {
+            assert!(parse_args(&args).is_err(), "{args:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(parse_args(&["--substeps"]).is_err());
+        assert!(parse_args(&["--substeps", "many"]).is_err());
+        assert!(parse_args(&["--gravity", "1000"]).is_err());
+        assert!(parse_args(&["--bogus", "1"]).is_err());
+    }
+}
@@ -1,162 +1,55 @@
-use macroquad::prelude::*;
-use rayon::prelude::*;
-
-const RADIUS: f32 = 3.0;
-// const CONSTRAINT_RADIUS: f32 = 300.0;
-// const SUBSTEPS: u32 = 8;
-
-#[derive(Clone, Copy, Debug, Default)]
-pub struct VerletObject {
-    position_current: Vec2,
-    position_old: Vec2,
-    acceleration: Vec2,
-}
-
-impl VerletObject {
-    pub fn new(position: Vec2) -> Self {
-        VerletObject {
-            position_current: position,
-            position_old: position,
-            acceleration: Vec2::new(0., 0.),
-        }
-    }
+mod args;
 
-    pub fn update_position(&mut self, dt: f32) {
-        let velocity = self.position_current - self.position_old;
-        // Save current position
-        self.position_old = self.position_current;
-        // Perform verlet integration
-        self.position_current += velocity + self.acceleration * dt * dt;
-        // Reset acceleration
-        self.acceleration = Vec2::new(0., 0.);
-    }
-
-    pub fn accelerate(&mut self, acceleration: Vec2) {
-        self.acceleration += acceleration;
-    }
+use std::sync::OnceLock;
 
-    pub fn get_position(&self) -> Vec2 {
-        self.position_current
+use args::Args;
+use macroquad::prelude::*;
+use verlet::force::{Attractor, Explosion, ForceGeneratorId};
+use verlet::{
+    nearest_object, Cloth, ClothMesh, Container, DistanceConstraint, Rope, SoftBody,
+    SoftBodyHandle, Solver, VerletObject, WallRestitution, WorldBounds,
+};
+
+const QUICKSAVE_PATH: &str = "quicksave.ron";
+/// Radius of the circular containers, if the window is large enough.
+const CONSTRAINT_RADIUS: f32 = 300.0;
+/// Reach and pull of the A key attractor and of the E key explosion.
+const FORCE_RADIUS: f32 = 200.0;
+const ATTRACTOR_STRENGTH: f32 = 4000.0;
+const EXPLOSION_STRENGTH: f32 = 40000.0;
+/// Objects across and down the K key cloth, and how far apart.
+const CLOTH_COLUMNS: usize = 40;
+const CLOTH_ROWS: usize = 25;
+const CLOTH_SPACING: f32 = 8.0;
+/// Cloth links tear when stretched beyond this many times their length.
+const CLOTH_TEAR_STRETCH: f32 = 1.6;
+/// Radius of the B key soft bodies.
+const BLOB_RADIUS: f32 = 40.0;
+
+/// Parsed once in `window_conf`, before the window exists.
+static ARGS: OnceLock<Args> = OnceLock::new();
+
+/// Container for each shape the C key cycles through: 0 box, 1 circle,
+/// 2 inverted circle. Circles are centred in the window.
+fn container_for_window(shape: usize, window_size: Vec2) -> Container {
+    let center = window_size / 2.0;
+    let radius = CONSTRAINT_RADIUS.min(center.min_element() - 10.0);
+    match shape {
+        0 => Container::Box,
+        1 => Container::Circle { center, radius },
+        _ => Container::InvertedCircle {
+            center,
+            radius: radius / 3.0,
+        },
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-pub struct DebugTimeInfo {
-    pub gravity_time: f32,
-    pub constraints_time: f32,
-    pub collisions_time: f32,
-    pub update_positions_time: f32,
-}
-
-#[derive(Debug, Default)]
-pub struct Solver {
-    gravity: Vec2,
-    // grid: Vec<Vec<Vec<usize>>>, // 1d 2d vec of point IDs
-}
-
-impl Solver {
-    pub fn new() -> Self {
-        // seperate the grid into RADIUS * 2 x RADIUS * 2 squares
-        Solver {
-            gravity: Vec2::new(0.0, 1000.0),
-            // grid: vec![vec![vec![]]],
-        }
-    }
-
-    pub fn update(
-        &mut self,
-        objects: &mut [VerletObject],
-        dt: f32,
-        substeps: u32,
-    ) -> DebugTimeInfo {
-        let sub_dt = dt / substeps as f32;
-        let mut gravity_time = 0.0;
-        let mut constraints_time = 0.0;
-        let mut collisions_time = 0.0;
-        let mut update_positions_time = 0.0;
-        for _ in 0..substeps {
-            gravity_time += Self::apply_gravity(objects, &self.gravity);
-            constraints_time += Self::apply_constraints(objects);
-            collisions_time += Self::solve_collisions(objects);
-            update_positions_time += Self::update_positions(objects, sub_dt);
-        }
-        DebugTimeInfo {
-            gravity_time,
-            constraints_time,
-            collisions_time,
-            update_positions_time,
-        }
-    }
-
-    // fn group_into_squares(&mut self, objects: &mut [VerletObject]) {
-    //     let screen_width = screen_width();
-    //     let screen_height = screen_height();
-    //     // The grid is RADIUS * 2 x RADIUS * 2 px
-    //     let grid_width = RADIUS * 2.0;
-    //     }
-
-    fn apply_gravity(objects: &mut [VerletObject], gravity: &Vec2) -> f32 {
-        let now = std::time::Instant::now();
-        for object in objects.iter_mut() {
-            object.accelerate(*gravity);
-        }
-        now.elapsed().as_secs_f32()
-    }
-
-    fn update_positions(objects: &mut [VerletObject], dt: f32) -> f32 {
-        let now = std::time::Instant::now();
-        objects.par_iter_mut().for_each(|object| {
-            object.update_position(dt);
-        });
-        now.elapsed().as_secs_f32()
-    }
-
-    fn apply_constraints(objects: &mut [VerletObject]) -> f32 {
-        let now = std::time::Instant::now();
-        let screen_width = screen_width();
-        let screen_height = screen_height();
-        for object in objects.iter_mut() {
-            // TODO: if the object is above, dont check below
-            if object.get_position().x < 0.0 + RADIUS + 1.0 {
-                // radius and 1 for border
-                object.position_current.x = 0.0 + RADIUS + 1.0;
-            }
-            if object.get_position().x > screen_width - RADIUS  - 1.0 {
-                object.position_current.x = screen_width - RADIUS - 1.0;
-            }
-            if object.get_position().y < 0.0 + RADIUS + 1.0 {
-                object.position_current.y = 0.0 + RADIUS  + 1.0;
-            }
-            if object.get_position().y > screen_height - RADIUS - 1.0 {
-                object.position_current.y = screen_height - RADIUS - 1.0;
-            }
-        }
-        now.elapsed().as_secs_f32()
-    }
-
-    fn solve_collisions(objects: &mut [VerletObject]) -> f32 {
-        // returns time in seconds
-        // Brute force O(n^2) collision detection
-        let now = std::time::Instant::now();
-        let object_count = objects.len();
-        for i in 0..object_count {
-            for j in i + 1..object_count {
-                let collision_axis = objects[i].get_position() - objects[j].get_position();
-                let distance: f32 = collision_axis.length();
-                if distance < 2.0 * RADIUS {
-                    // Collision detected
-                    let n = collision_axis / distance;
-                    let delta: f32 = 2.0 * RADIUS - distance;
-                    objects[i].position_current += 0.5 * delta * n;
-                    objects[j].position_current -= 0.5 * delta * n;
-                }
-            }
-        }
-
-        now.elapsed().as_secs_f32()
+fn container_shape(container: Container) -> usize {
+    match container {
+        Container::Box => 0,
+        Container::Circle { .. } => 1,
+        Container::InvertedCircle { .. } => 2,
     }
-
-    
 }
 
 fn convert_velocity_to_color(velocity: Vec2) -> Color {
@@ -183,6 +76,15 @@ fn convert_velocity_to_color(velocity: Vec2) -> Color {
     Color::new(r, g, b, 1.0)
 }
 
+fn convert_stretch_to_color(stretch: f32) -> Color {
+    // relaxed - blue
+    // stretched to the tearing point - red
+    let normalized_stretch = ((stretch - 1.0) / (CLOTH_TEAR_STRETCH - 1.0)).clamp(0.0, 1.0);
+    let hue = 240.0 * (1.0 - normalized_stretch);
+    let (r, g, b) = hsl_to_rgb(hue, 0.8, 0.5);
+    Color::new(r, g, b, 1.0)
+}
+
 fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
     let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
     let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
@@ -205,18 +107,65 @@ fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
     (rp + m, gp + m, bp + m)
 }
 
-#[macroquad::main("BasicShapes")]
+fn window_conf() -> Conf {
+    let args = ARGS.get_or_init(Args::from_env);
+    Conf {
+        window_title: "BasicShapes".to_owned(),
+        window_width: args.width,
+        window_height: args.height,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
 async fn main() {
-    // Setup a point in the middle of the screen
-    let mut objects = vec![VerletObject::new(Vec2::new(
-        screen_width() / 2.0,
-        screen_height() / 2.0,
-    ))];
+    let args = ARGS.get_or_init(Args::from_env);
+
+    let seed = args.seed.unwrap_or_else(|| miniquad::date::now() as u64);
+    rand::srand(seed);
 
     let mut solver = Solver::new();
-    let mut last_mouse_input: f64 = 0.0;
+    solver.set_substeps(args.substeps);
+    solver.set_gravity(args.gravity);
+    solver.set_damping(args.damping);
+    solver.set_drag(args.drag);
+    solver.set_wall_material(args.material);
+    if let Some(restitution) = args.restitution {
+        solver.set_wall_restitution(WallRestitution::uniform(restitution));
+    }
+    if let Some(threads) = args.threads {
+        if let Err(error) = solver.set_thread_count(threads) {
+            error!("Could not start {} solver threads: {}", threads, error);
+        }
+    }
 
-    let mut substeps = 8;
+    let mut window_size = Vec2::new(screen_width(), screen_height());
+    solver.set_bounds(WorldBounds::from_size(window_size));
+
+    let mut objects = match &args.scene {
+        Some(path) => match solver.load_scene(path) {
+            Ok(objects) => objects,
+            Err(error) => {
+                eprintln!("Could not load {}: {}", path.display(), error);
+                std::process::exit(1);
+            }
+        },
+        // Setup a point in the middle of the screen
+        None => vec![VerletObject::new(window_size / 2.0)
+            .with_radius(args.radius)
+            .with_material(args.material)],
+    };
+    let mut shape = container_shape(solver.container());
+
+    let mut last_mouse_input: f64 = 0.0;
+    let mut attractor: Option<ForceGeneratorId> = None;
+    // Mouse path of the rope being drawn with shift held
+    let mut rope_path: Option<Vec<Vec2>> = None;
+    let mut cloths: Vec<ClothMesh> = Vec::new();
+    let mut blobs: Vec<SoftBodyHandle> = Vec::new();
+    let mut last_mouse = Vec2::from(mouse_position());
+    // Links broken since the last clear
+    let mut broken_links = 0;
 
     loop {
         // Clear the screen
@@ -225,48 +174,299 @@ async fn main() {
         let screen_width = screen_width();
         let screen_height = screen_height();
 
+        // Keep the world in sync with the window
+        let resized = window_size != Vec2::new(screen_width, screen_height);
+        if resized {
+            window_size = Vec2::new(screen_width, screen_height);
+            solver.set_bounds(WorldBounds::from_size(window_size));
+        }
+
         // If the space is pressed, clear the points
         if is_key_pressed(KeyCode::Space) {
             objects.clear();
+            solver.clear_distance_constraints();
+            solver.clear_force_generators();
+            attractor = None;
+            rope_path = None;
+            cloths.clear();
+            blobs.clear();
+            broken_links = 0;
+            solver.release();
+        }
+
+        // Link the two newest points with a stick
+        if is_key_pressed(KeyCode::L) && objects.len() >= 2 {
+            let last = objects.len() - 1;
+            solver.add_distance_constraint(DistanceConstraint::between(
+                &objects,
+                last - 1,
+                last,
+                1.0,
+            ));
+        }
+
+        // Switch the container shape
+        if is_key_pressed(KeyCode::C) {
+            shape = (shape + 1) % 3;
+        }
+        if resized || is_key_pressed(KeyCode::C) {
+            solver.set_container(container_for_window(shape, window_size));
+        }
+
+        // Quicksave and quickload
+        if is_key_pressed(KeyCode::F5) {
+            match solver.save_scene(&objects, QUICKSAVE_PATH) {
+                Ok(()) => info!("Saved scene to {}", QUICKSAVE_PATH),
+                Err(error) => error!("Quicksave failed: {}", error),
+            }
+        }
+        if is_key_pressed(KeyCode::F9) {
+            match solver.load_scene(QUICKSAVE_PATH) {
+                Ok(loaded) => {
+                    objects = loaded;
+                    // The grabbed index was into the old objects
+                    solver.release();
+                    // Scenes do not know about cloths and soft bodies, their links
+                    // stay as sticks and the pressure goes
+                    cloths.clear();
+                    for blob in blobs.drain(..) {
+                        solver.remove_force_generator(blob.pressure());
+                    }
+                    shape = container_shape(solver.container());
+                }
+                Err(error) => error!("Quickload failed: {}", error),
+            }
         }
 
         // Change the number of substeps
         let (_, mouse_wheel_y) = mouse_wheel();
         if mouse_wheel_y > 0.0 {
-            substeps = (substeps + 1).min(32);
+            solver.set_substeps((solver.substeps() + 1).min(32));
         } else if mouse_wheel_y < 0.0 {
-            substeps = (substeps - 1).max(1);
+            solver.set_substeps(solver.substeps() - 1);
         }
 
         let fps = (1.0 / get_frame_time()).round();
 
+        // Hang a cloth from the top of the window
+        let mouse = Vec2::from(mouse_position());
+        if is_key_pressed(KeyCode::K) {
+            let width = (CLOTH_COLUMNS - 1) as f32 * CLOTH_SPACING;
+            let origin = Vec2::new((window_size.x - width) / 2.0, 40.0);
+            cloths.push(
+                Cloth::new(origin, CLOTH_COLUMNS, CLOTH_ROWS, CLOTH_SPACING)
+                    .with_tear_stretch(CLOTH_TEAR_STRETCH)
+                    .with_radius(args.radius)
+                    .build(&mut solver, &mut objects),
+            );
+        }
+
+        // Spawn a soft body under the mouse
+        if is_key_pressed(KeyCode::B) {
+            // Enough objects for them to touch around the outline
+            let points = (std::f32::consts::TAU * BLOB_RADIUS / (2.0 * args.radius)).ceil();
+            blobs.push(
+                SoftBody::circle(mouse, BLOB_RADIUS, points as usize)
+                    .with_pressure(args.blob_pressure)
+                    .with_stiffness(args.blob_stiffness)
+                    .with_radius(args.radius)
+                    .build(&mut solver, &mut objects),
+            );
+        }
+
+        // Cut the cloths along the mouse's path while X is held
+        if is_key_down(KeyCode::X) {
+            for cloth in &cloths {
+                cloth.cut(&mut solver, &objects, last_mouse, mouse);
+            }
+        }
+        last_mouse = mouse;
+
+        // Draw a rope with shift held, made when the button is released
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if is_mouse_button_pressed(MouseButton::Left) && shift {
+            rope_path = Some(vec![mouse]);
+        }
+        if let Some(path) = &mut rope_path {
+            if path.last().unwrap().distance(mouse) >= args.radius {
+                path.push(mouse);
+            }
+        }
+        if is_mouse_button_released(MouseButton::Left) {
+            if let Some(path) = rope_path.take() {
+                let length: f32 = path.windows(2).map(|w| w[0].distance(w[1])).sum();
+                // Links as long as the points are wide
+                let segments = (length / (2.0 * args.radius)).ceil() as usize;
+                if segments > 0 {
+                    Rope::along(path, segments)
+                        .with_radius(args.radius)
+                        .pinned(true, false)
+                        .build(&mut solver, &mut objects);
+                }
+            }
+        }
+
+        // Grab the point under the cursor and drag it around
+        if is_mouse_button_pressed(MouseButton::Left) && rope_path.is_none() {
+            if let Some(index) = nearest_object(&objects, mouse) {
+                let object = &objects[index];
+                if object.get_position().distance(mouse) < object.get_radius() + 4.0 {
+                    solver.grab(&objects, index);
+                }
+            }
+        }
+        if is_mouse_button_released(MouseButton::Left) {
+            solver.release();
+        }
+        solver.set_grab_target(mouse);
+
+        // Pull points towards the cursor while A is held, push them away with shift
+        if let Some(id) = attractor.take() {
+            solver.remove_force_generator(id);
+        }
+        if is_key_down(KeyCode::A) {
+            let field = if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+                Attractor::repulsor(mouse, FORCE_RADIUS, ATTRACTOR_STRENGTH)
+            } else {
+                Attractor::new(mouse, FORCE_RADIUS, ATTRACTOR_STRENGTH)
+            };
+            attractor = Some(solver.add_force_generator(field));
+        }
+
+        // Blow the points away from the cursor
+        if is_key_pressed(KeyCode::E) {
+            solver.add_force_generator(Explosion::new(
+                mouse,
+                FORCE_RADIUS,
+                EXPLOSION_STRENGTH,
+                0.05,
+            ));
+        }
+
         // Add a point
-        if is_mouse_button_down(MouseButton::Left) {
+        if is_mouse_button_down(MouseButton::Left)
+            && solver.grabbed().is_none()
+            && rope_path.is_none()
+        {
             let current_time = get_time();
             if current_time - last_mouse_input > 0.01 {
                 last_mouse_input = current_time;
                 let mouse_position = mouse_position();
-                objects.push(VerletObject::new(Vec2::new(
-                    mouse_position.0,
-                    mouse_position.1,
-                )));
+                // A little jitter so points spawned on the same spot do not stack perfectly
+                let jitter = Vec2::new(rand::gen_range(-0.5, 0.5), rand::gen_range(-0.5, 0.5));
+                objects.push(
+                    VerletObject::new(Vec2::new(mouse_position.0, mouse_position.1) + jitter)
+                        .with_radius(args.radius)
+                        .with_material(args.material),
+                );
+            }
+        }
+
+        // Toggle the pin on the nearest point
+        if is_mouse_button_pressed(MouseButton::Right) {
+            let mouse_position = mouse_position();
+            let mouse_position = Vec2::new(mouse_position.0, mouse_position.1);
+            if let Some(index) = nearest_object(&objects, mouse_position) {
+                let object = &mut objects[index];
+                object.set_pinned(!object.is_pinned());
             }
         }
 
         // Update the solver
-        let timings = solver.update(&mut objects, get_frame_time(), substeps);
+        let report = solver.advance(&mut objects, get_frame_time());
+        let timings = report.timings;
+        broken_links += solver.drain_broken_constraints().count();
+
+        // Draw the constraint
+        let bounds = solver.bounds();
+        match solver.container() {
+            Container::Box => {
+                draw_rectangle_lines(
+                    bounds.origin.x,
+                    bounds.origin.y,
+                    bounds.size.x,
+                    bounds.size.y,
+                    2.0,
+                    WHITE,
+                );
+            }
+            Container::Circle { center, radius } => {
+                draw_circle_lines(center.x, center.y, radius, 2.0, WHITE);
+            }
+            Container::InvertedCircle { center, radius } => {
+                draw_rectangle_lines(
+                    bounds.origin.x,
+                    bounds.origin.y,
+                    bounds.size.x,
+                    bounds.size.y,
+                    2.0,
+                    WHITE,
+                );
+                draw_circle(center.x, center.y, radius, DARKGRAY);
+            }
+        }
+
+        // Draw the attractor's reach
+        if attractor.is_some() {
+            draw_circle_lines(mouse.x, mouse.y, FORCE_RADIUS, 1.0, DARKGRAY);
+        }
 
-        // Draw the constraint (entire screen)
-        draw_rectangle_lines(0.0, 0.0, screen_width, screen_height, 2.0, WHITE);
+        // Draw the rope being drawn
+        if let Some(path) = &rope_path {
+            for piece in path.windows(2) {
+                draw_line(piece[0].x, piece[0].y, piece[1].x, piece[1].y, 1.0, YELLOW);
+            }
+        }
+
+        // Draw the cloths, coloured by how stretched they are
+        let in_cloth = |index: usize| cloths.iter().any(|cloth| cloth.contains(index));
+        for cloth in &cloths {
+            for triangle in cloth.triangles(&solver, &objects) {
+                let [a, b, c] = triangle
+                    .indices
+                    .map(|index| objects[index].interpolated_position(report.alpha));
+                draw_triangle(a, b, c, convert_stretch_to_color(triangle.stretch));
+            }
+        }
+
+        // Fill the soft bodies
+        for blob in &blobs {
+            let outline: Vec<Vec2> = blob
+                .indices()
+                .map(|index| objects[index].interpolated_position(report.alpha))
+                .collect();
+            let center = outline.iter().sum::<Vec2>() / outline.len() as f32;
+            for (i, &a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
+                draw_triangle(center, a, b, Color::new(0.3, 0.6, 1.0, 0.4));
+            }
+        }
+
+        // Draw the sticks
+        for constraint in solver
+            .distance_constraints()
+            .iter()
+            .filter(|constraint| !in_cloth(constraint.a))
+        {
+            let a = objects[constraint.a].interpolated_position(report.alpha);
+            let b = objects[constraint.b].interpolated_position(report.alpha);
+            draw_line(a.x, a.y, b.x, b.y, 1.0, GRAY);
+        }
 
         // Draw the points
-        for object in objects.iter() {
-            draw_circle(
-                object.get_position().x,
-                object.get_position().y,
-                RADIUS,
-                convert_velocity_to_color(object.get_position() - object.position_old),
-            );
+        for (index, object) in objects.iter().enumerate() {
+            if in_cloth(index) && !object.is_pinned() && solver.grabbed() != Some(index) {
+                continue;
+            }
+            let color = if object.is_pinned() {
+                WHITE
+            } else if solver.grabbed() == Some(index) {
+                YELLOW
+            } else {
+                convert_velocity_to_color(object.get_velocity())
+            };
+            let position = object.interpolated_position(report.alpha);
+            draw_circle(position.x, position.y, object.get_radius(), color);
         }
 
         // info!("First point pos: {:?}", objects[0].get_position());
@@ -281,52 +481,66 @@ async fn main() {
             20.0,
             WHITE,
         );
-        draw_text(&format!("Substeps: {}", substeps), 10.0, 60.0, 20.0, WHITE);
-
-        // Top right text
-        draw_text("CLICK TO ADD POINT", screen_width - 165., 20.0, 20.0, WHITE);
-        draw_text("SPACE TO CLEAR", screen_width - 132., 40.0, 20.0, WHITE);
-        draw_text(
-            "SCROLL TO CHANGE SUBSTEPS",
-            screen_width - 228.,
-            60.0,
-            20.0,
-            WHITE,
-        );
-
-        // Draw the timings in the bottom left
-        draw_text(
-            &format!("Gravity: {:.2}ms", timings.gravity_time * 1000.0),
-            10.0,
-            screen_height - 80.0,
-            20.0,
-            WHITE,
-        );
         draw_text(
-            &format!("Constraints: {:.2}ms", timings.constraints_time * 1000.0),
+            &format!("Substeps: {}", solver.substeps()),
             10.0,
-            screen_height - 60.0,
-            20.0,
-            WHITE,
-        );
-        draw_text(
-            &format!("Collisions: {:.2}ms", timings.collisions_time * 1000.0),
-            10.0,
-            screen_height - 40.0,
+            60.0,
             20.0,
             WHITE,
         );
         draw_text(
-            &format!(
-                "Update Positions: {:.2}ms",
-                timings.update_positions_time * 1000.0
-            ),
+            &format!("Broken links: {}", broken_links),
             10.0,
-            screen_height - 20.0,
+            80.0,
             20.0,
             WHITE,
         );
 
+        // Top right text
+        let help = [
+            "CLICK TO ADD POINT, DRAG A POINT TO MOVE IT",
+            "RIGHT CLICK TO PIN",
+            "SHIFT+DRAG TO DRAW A ROPE",
+            "K FOR A CLOTH, HOLD X TO CUT IT",
+            "B FOR A SOFT BODY",
+            "L TO LINK LAST TWO POINTS",
+            "HOLD A TO ATTRACT, SHIFT+A TO REPEL",
+            "E FOR AN EXPLOSION",
+            "C TO CHANGE CONTAINER",
+            "F5 QUICKSAVE, F9 QUICKLOAD",
+            "SPACE TO CLEAR",
+            "SCROLL TO CHANGE SUBSTEPS",
+        ];
+        for (line, text) in help.iter().enumerate() {
+            let width = measure_text(text, None, 20, 1.0).width;
+            draw_text(
+                text,
+                screen_width - width - 10.0,
+                20.0 + 20.0 * line as f32,
+                20.0,
+                WHITE,
+            );
+        }
+
+        // Draw the timings in the bottom left
+        let timings = [
+            ("Gravity", timings.gravity_time),
+            ("Forces", timings.forces_time),
+            ("Constraints", timings.constraints_time),
+            ("Collisions", timings.collisions_time),
+            ("Sticks", timings.distance_constraints_time),
+            ("Update Positions", timings.update_positions_time),
+        ];
+        for (line, (name, time)) in timings.iter().rev().enumerate() {
+            draw_text(
+                &format!("{}: {:.2}ms", name, time * 1000.0),
+                10.0,
+                screen_height - 20.0 - 20.0 * line as f32,
+                20.0,
+                WHITE,
+            );
+        }
+
         // Finish the frame
         next_frame().await
     }
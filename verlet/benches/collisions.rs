@@ -0,0 +1,76 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use verlet::collision::{self, Broadphase, BruteForce, CollisionGrid, SpatialHash};
+use verlet::{Vec2, VerletObject, WorldBounds, DEFAULT_RADIUS};
+
+/// Objects scattered over a square sized so they cover about half of it,
+/// roughly the density of a settled pile.
+fn scene(count: usize) -> (Vec<VerletObject>, WorldBounds) {
+    let side = (count as f32 * std::f32::consts::PI * DEFAULT_RADIUS * DEFAULT_RADIUS / 0.5).sqrt();
+    let bounds = WorldBounds::from_size(Vec2::splat(side));
+    let mut state = count as u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 40) as f32 / (1u64 << 24) as f32
+    };
+    let objects = (0..count)
+        .map(|_| VerletObject::new(Vec2::new(next(), next()) * side))
+        .collect();
+    (objects, bounds)
+}
+
+fn collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collisions");
+    group.sample_size(20);
+    for count in [5_000, 20_000, 50_000] {
+        let (objects, bounds) = scene(count);
+        let mut grid = CollisionGrid::new();
+        grid.rebuild_with_cell_size(&objects, &bounds, 2.0 * DEFAULT_RADIUS);
+
+        group.bench_with_input(BenchmarkId::new("serial", count), &grid, |b, grid| {
+            b.iter_batched_ref(
+                || objects.clone(),
+                |objects| grid.solve_collisions(objects),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", count), &grid, |b, grid| {
+            b.iter_batched_ref(
+                || objects.clone(),
+                |objects| grid.solve_collisions_parallel(objects),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Rebuild and solve with each broadphase on the same scene.
+fn broadphases(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadphase");
+    group.sample_size(10);
+    for count in [1_000, 5_000] {
+        let (objects, bounds) = scene(count);
+        let mut broadphases: [(&str, Box<dyn Broadphase>); 3] = [
+            ("brute_force", Box::new(BruteForce::new())),
+            ("dense_grid", Box::new(CollisionGrid::new())),
+            ("spatial_hash", Box::new(SpatialHash::new())),
+        ];
+        for (name, broadphase) in &mut broadphases {
+            group.bench_function(BenchmarkId::new(*name, count), |b| {
+                b.iter_batched_ref(
+                    || objects.clone(),
+                    |objects| {
+                        collision::solve_collisions(broadphase.as_mut(), objects, &bounds, false)
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, collisions, broadphases);
+criterion_main!(benches);
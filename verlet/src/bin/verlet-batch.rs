@@ -0,0 +1,156 @@
+//! Headless batch runner: loads a scene, advances it a fixed number of steps and
+//! writes the final state and per-step timings. Needs no display.
+//!
+//! ```text
+//! verlet-batch <scene> [--steps N] [--dt SECONDS] [--substeps N] [--threads N]
+//!              [--deterministic] [--output PATH] [--timings PATH]
+//! ```
+//!
+//! The scene can be a RON scene or a binary snapshot. The final state is written
+//! as a snapshot unless the output path ends in `.ron`. Timings are written as
+//! CSV, one row per step, in seconds, along with the number of constraints that
+//! broke in the step.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use verlet::{Scene, Snapshot, Solver, VerletObject, SNAPSHOT_MAGIC};
+
+const USAGE: &str = "usage: verlet-batch <scene> [--steps N] [--dt SECONDS] [--substeps N] \
+[--threads N] [--deterministic] [--output PATH] [--timings PATH]";
+
+struct Args {
+    scene: PathBuf,
+    steps: u64,
+    dt: f32,
+    substeps: Option<u32>,
+    threads: Option<usize>,
+    deterministic: bool,
+    output: PathBuf,
+    timings: PathBuf,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut scene = None;
+    let mut parsed = Args {
+        scene: PathBuf::new(),
+        steps: 600,
+        dt: 1.0 / 60.0,
+        substeps: None,
+        threads: None,
+        deterministic: false,
+        output: PathBuf::from("final.bin"),
+        timings: PathBuf::from("timings.csv"),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--steps" => parsed.steps = parse(&value()?)?,
+            "--dt" => parsed.dt = parse_positive(&arg, &value()?)?,
+            "--substeps" => parsed.substeps = Some(parse(&value()?)?),
+            "--threads" => parsed.threads = Some(parse(&value()?)?),
+            "--deterministic" => parsed.deterministic = true,
+            "--output" => parsed.output = PathBuf::from(value()?),
+            "--timings" => parsed.timings = PathBuf::from(value()?),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+            _ if scene.is_none() => scene = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+    parsed.scene = scene.ok_or("missing scene file")?;
+    Ok(parsed)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value {value}"))
+}
+
+/// A finite number greater than 0.
+fn parse_positive(arg: &str, value: &str) -> Result<f32, String> {
+    let parsed: f32 = parse(value)?;
+    if parsed.is_finite() && parsed > 0.0 {
+        Ok(parsed)
+    } else {
+        Err(format!(
+            "{arg} must be a finite number greater than 0, got {value}"
+        ))
+    }
+}
+
+/// Load a RON scene or a binary snapshot, whichever `path` holds.
+fn load(solver: &mut Solver, path: &Path) -> Result<Vec<VerletObject>, Box<dyn Error>> {
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(&SNAPSHOT_MAGIC) {
+        Ok(solver.apply_snapshot(Snapshot::decode(&bytes)?))
+    } else {
+        let scene = Scene::from_ron(std::str::from_utf8(&bytes)?)?;
+        Ok(solver.apply_scene(scene))
+    }
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let mut solver = Solver::new();
+    let mut objects = load(&mut solver, &args.scene)?;
+    if let Some(substeps) = args.substeps {
+        solver.set_substeps(substeps);
+    }
+    if let Some(threads) = args.threads {
+        solver.set_thread_count(threads)?;
+    }
+    solver.set_deterministic(args.deterministic);
+
+    let mut timings = BufWriter::new(File::create(&args.timings)?);
+    writeln!(
+        timings,
+        "step,gravity,forces,constraints,collisions,distance_constraints,update_positions,broken"
+    )?;
+    let substeps = solver.substeps();
+    for _ in 0..args.steps {
+        let info = solver.update(&mut objects, args.dt, substeps);
+        writeln!(
+            timings,
+            "{},{},{},{},{},{},{},{}",
+            solver.step_count(),
+            info.gravity_time,
+            info.forces_time,
+            info.constraints_time,
+            info.collisions_time,
+            info.distance_constraints_time,
+            info.update_positions_time,
+            solver.drain_broken_constraints().count(),
+        )?;
+    }
+    timings.flush()?;
+
+    if args
+        .output
+        .extension()
+        .is_some_and(|extension| extension == "ron")
+    {
+        solver.save_scene(&objects, &args.output)?;
+    } else {
+        solver.save_snapshot(&objects, &args.output)?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("verlet-batch: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Axis-aligned rectangle the particles are kept inside.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorldBounds {
+    pub origin: Vec2,
+    pub size: Vec2,
+}
+
+impl WorldBounds {
+    pub fn new(origin: Vec2, size: Vec2) -> Self {
+        WorldBounds { origin, size }
+    }
+
+    /// Bounds anchored at the origin, e.g. a window of the given size.
+    pub fn from_size(size: Vec2) -> Self {
+        WorldBounds::new(Vec2::ZERO, size)
+    }
+
+    pub fn min(&self) -> Vec2 {
+        self.origin
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.origin + self.size
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min()).all() && point.cmple(self.max()).all()
+    }
+
+    /// Clamp `point` so that it stays at least `margin` away from every edge.
+    /// If the bounds are too small for the margin, the point goes to the centre.
+    pub fn clamp(&self, point: Vec2, margin: f32) -> Vec2 {
+        let min = self.min() + margin;
+        let max = self.max() - margin;
+        let center = self.origin + self.size * 0.5;
+        let clamp_axis = |value: f32, min: f32, max: f32, center: f32| {
+            if min <= max {
+                value.clamp(min, max)
+            } else {
+                center
+            }
+        };
+        Vec2::new(
+            clamp_axis(point.x, min.x, max.x, center.x),
+            clamp_axis(point.y, min.y, max.y, center.y),
+        )
+    }
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        // Matches the default macroquad window
+        WorldBounds::from_size(Vec2::new(800.0, 600.0))
+    }
+}
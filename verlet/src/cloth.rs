@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use glam::Vec2;
+
+use crate::object::unused_collision_group;
+use crate::{DistanceConstraint, Solver, VerletObject, DEFAULT_RADIUS};
+
+/// Builds a rectangular sheet of cloth: a grid of objects held together by
+/// structural links (to the right and below), shear links (across each
+/// square) and bend links (skipping one object), with its top row pinned.
+#[derive(Clone, Copy, Debug)]
+pub struct Cloth {
+    origin: Vec2,
+    columns: usize,
+    rows: usize,
+    spacing: f32,
+    structural_stiffness: f32,
+    shear_stiffness: f32,
+    bend_stiffness: f32,
+    pin_top: bool,
+    tear_stretch: Option<f32>,
+    radius: f32,
+}
+
+impl Cloth {
+    /// `columns` by `rows` objects, `spacing` apart, with the top left one at `origin`.
+    pub fn new(origin: Vec2, columns: usize, rows: usize, spacing: f32) -> Self {
+        Cloth {
+            origin,
+            columns: columns.max(1),
+            rows: rows.max(1),
+            spacing,
+            structural_stiffness: 1.0,
+            shear_stiffness: 0.5,
+            bend_stiffness: 0.2,
+            pin_top: true,
+            tear_stretch: None,
+            radius: DEFAULT_RADIUS,
+        }
+    }
+
+    /// Stiffness of each kind of link, see [`DistanceConstraint`]. A stiffness
+    /// of 0 leaves that kind out.
+    pub fn with_stiffness(mut self, structural: f32, shear: f32, bend: f32) -> Self {
+        self.structural_stiffness = structural;
+        self.shear_stiffness = shear;
+        self.bend_stiffness = bend;
+        self
+    }
+
+    /// Pin the top row in place. On by default.
+    pub fn pin_top(mut self, pin_top: bool) -> Self {
+        self.pin_top = pin_top;
+        self
+    }
+
+    /// Make the links break once stretched beyond `stretch` times their rest
+    /// length, see [`DistanceConstraint::max_strain`].
+    pub fn with_tear_stretch(mut self, stretch: f32) -> Self {
+        self.tear_stretch = Some(stretch);
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Append the cloth's objects to `objects`, row by row, and its links to
+    /// the solver. The objects share a collision group so the links are not
+    /// fought by collisions between neighbours.
+    pub fn build(&self, solver: &mut Solver, objects: &mut Vec<VerletObject>) -> ClothMesh {
+        let mesh = ClothMesh {
+            first: objects.len(),
+            columns: self.columns,
+            rows: self.rows,
+        };
+        let group = unused_collision_group(objects);
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let position = self.origin + Vec2::new(column as f32, row as f32) * self.spacing;
+                let mut object = VerletObject::new(position)
+                    .with_radius(self.radius)
+                    .with_collision_group(group);
+                if row == 0 && self.pin_top {
+                    object.pin();
+                }
+                objects.push(object);
+            }
+        }
+
+        let links = [
+            // Structural
+            ((1, 0), self.structural_stiffness),
+            ((0, 1), self.structural_stiffness),
+            // Shear
+            ((1, 1), self.shear_stiffness),
+            ((-1, 1), self.shear_stiffness),
+            // Bend
+            ((2, 0), self.bend_stiffness),
+            ((0, 2), self.bend_stiffness),
+        ];
+        for ((dx, dy), stiffness) in links {
+            if stiffness <= 0.0 {
+                continue;
+            }
+            for row in 0..self.rows {
+                for column in 0..self.columns {
+                    if let Some(other) = mesh.offset(column, row, dx, dy) {
+                        let a = mesh.index(column, row);
+                        let mut link = DistanceConstraint::between(objects, a, other, stiffness);
+                        link.max_strain = self.tear_stretch.map(|stretch| stretch - 1.0);
+                        solver.add_distance_constraint(link);
+                    }
+                }
+            }
+        }
+        mesh
+    }
+}
+
+/// A triangle of a [`ClothMesh`] whose edges are all still linked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClothTriangle {
+    /// Indices into the objects.
+    pub indices: [usize; 3],
+    /// Mean of the edges' current length over their rest length.
+    pub stretch: f32,
+}
+
+/// Handle on a cloth made by [`Cloth::build`], to cut it and draw it.
+///
+/// The links live in the solver like any other distance constraint; the mesh
+/// only remembers where the cloth's objects are, which stays valid as long as
+/// the objects before and in the cloth are not removed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClothMesh {
+    first: usize,
+    columns: usize,
+    rows: usize,
+}
+
+impl ClothMesh {
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Index into the objects of the object at `column`, `row`.
+    pub fn index(&self, column: usize, row: usize) -> usize {
+        self.first + row * self.columns + column
+    }
+
+    /// Indices of all the cloth's objects.
+    pub fn indices(&self) -> std::ops::Range<usize> {
+        self.first..self.first + self.columns * self.rows
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices().contains(&index)
+    }
+
+    fn offset(&self, column: usize, row: usize, dx: isize, dy: isize) -> Option<usize> {
+        let column = column.checked_add_signed(dx)?;
+        let row = row.checked_add_signed(dy)?;
+        (column < self.columns && row < self.rows).then(|| self.index(column, row))
+    }
+
+    fn owns(&self, constraint: &DistanceConstraint) -> bool {
+        self.contains(constraint.a) && self.contains(constraint.b)
+    }
+
+    /// Break the cloth's links that cross the segment from `from` to `to`,
+    /// e.g. the mouse's path over a frame, reporting them like torn ones.
+    /// Returns the number of links broken.
+    pub fn cut(
+        &self,
+        solver: &mut Solver,
+        objects: &[VerletObject],
+        from: Vec2,
+        to: Vec2,
+    ) -> usize {
+        solver.break_distance_constraints(|constraint| {
+            self.owns(constraint)
+                && segments_cross(
+                    objects[constraint.a].get_position(),
+                    objects[constraint.b].get_position(),
+                    from,
+                    to,
+                )
+        })
+    }
+
+    /// Triangles still held together by links, to draw the cloth as a filled
+    /// mesh. Each square is split along whichever diagonal is still linked.
+    pub fn triangles(&self, solver: &Solver, objects: &[VerletObject]) -> Vec<ClothTriangle> {
+        // Rest length of every link of the cloth, by its ends
+        let links: HashMap<(usize, usize), f32> = solver
+            .distance_constraints()
+            .iter()
+            .filter(|constraint| self.owns(constraint))
+            .map(|constraint| {
+                let key = (
+                    constraint.a.min(constraint.b),
+                    constraint.a.max(constraint.b),
+                );
+                (key, constraint.rest_length)
+            })
+            .collect();
+        let stretch = |a: usize, b: usize| {
+            let rest_length = links.get(&(a.min(b), a.max(b)))?;
+            let length = objects[a]
+                .get_position()
+                .distance(objects[b].get_position());
+            Some(length / rest_length)
+        };
+        let triangle = |indices: [usize; 3]| {
+            let [a, b, c] = indices;
+            let total = stretch(a, b)? + stretch(b, c)? + stretch(c, a)?;
+            Some(ClothTriangle {
+                indices,
+                stretch: total / 3.0,
+            })
+        };
+
+        let mut triangles = Vec::new();
+        for row in 0..self.rows.saturating_sub(1) {
+            for column in 0..self.columns.saturating_sub(1) {
+                let top_left = self.index(column, row);
+                let top_right = self.index(column + 1, row);
+                let bottom_left = self.index(column, row + 1);
+                let bottom_right = self.index(column + 1, row + 1);
+                let split = if links.contains_key(&(top_left, bottom_right)) {
+                    [
+                        triangle([top_left, top_right, bottom_right]),
+                        triangle([top_left, bottom_right, bottom_left]),
+                    ]
+                } else {
+                    [
+                        triangle([top_left, top_right, bottom_left]),
+                        triangle([top_right, bottom_right, bottom_left]),
+                    ]
+                };
+                triangles.extend(split.into_iter().flatten());
+            }
+        }
+        triangles
+    }
+}
+
+/// Whether the segments `a`–`b` and `c`–`d` intersect.
+fn segments_cross(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let side = |p: Vec2, q: Vec2, r: Vec2| (q - p).perp_dot(r - p);
+    let (d1, d2) = (side(c, d, a), side(c, d, b));
+    let (d3, d4) = (side(a, b, c), side(a, b, d));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
@@ -0,0 +1,217 @@
+use glam::Vec2;
+use rayon::prelude::*;
+
+use super::{resolve_pair, resolve_pair_in, Broadphase};
+use crate::{VerletObject, WorldBounds};
+
+/// Raw access to the objects for strips of the grid solved on different threads.
+#[derive(Clone, Copy)]
+struct SharedObjects(*mut VerletObject);
+
+// SAFETY: only used by `CollisionGrid::solve_collisions_parallel`, where strips
+// running at the same time never reach the same object.
+unsafe impl Send for SharedObjects {}
+unsafe impl Sync for SharedObjects {}
+
+impl SharedObjects {
+    /// # Safety
+    /// `i` and `j` must be distinct, in bounds, and not borrowed anywhere else.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn pair(&self, i: usize, j: usize) -> (&mut VerletObject, &mut VerletObject) {
+        (&mut *self.0.add(i), &mut *self.0.add(j))
+    }
+}
+
+/// Cells allowed per object, so a few small objects in a large world do not
+/// make a huge, mostly empty grid to clear every substep.
+const CELLS_PER_OBJECT: usize = 4;
+/// Cells any grid may have however few objects there are.
+const MIN_CELLS: usize = 1024;
+
+/// Uniform grid broadphase covering the world bounds.
+///
+/// Objects are bucketed by the cell containing their centre, so with cells at
+/// least as wide as the largest diameter two objects can only touch if their
+/// cells are neighbours. Objects outside the bounds are clamped into the edge cells.
+/// Cells are stored column by column. Cells grow beyond the requested size
+/// when the bounds would otherwise need many more cells than there are objects.
+#[derive(Debug, Default)]
+pub struct CollisionGrid {
+    origin: Vec2,
+    cell_size: f32,
+    cols: usize,
+    rows: usize,
+    // cell_objects[cell_start[c]..cell_start[c + 1]] are the objects in cell c
+    cell_start: Vec<usize>,
+    cell_objects: Vec<usize>,
+    object_cells: Vec<usize>,
+}
+
+impl CollisionGrid {
+    pub fn new() -> Self {
+        CollisionGrid::default()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Smallest cell size that still finds every contact: the largest diameter.
+    pub fn cell_size_for(objects: &[VerletObject]) -> f32 {
+        2.0 * objects
+            .iter()
+            .fold(0.0, |max, object| object.radius.max(max))
+    }
+
+    /// Re-bucket every object into cells of at least `cell_size`, which must
+    /// be at least [`CollisionGrid::cell_size_for`] to find every contact.
+    pub fn rebuild_with_cell_size(
+        &mut self,
+        objects: &[VerletObject],
+        bounds: &WorldBounds,
+        cell_size: f32,
+    ) {
+        self.origin = bounds.origin;
+        // An empty scene or zero-sized objects would give zero-sized cells
+        self.cell_size = if cell_size > 0.0 {
+            cell_size
+        } else {
+            bounds.size.max_element().max(1.0)
+        };
+        let max_cells = (objects.len() * CELLS_PER_OBJECT).max(MIN_CELLS);
+        let area = bounds.size.x.max(0.0) * bounds.size.y.max(0.0);
+        self.cell_size = self.cell_size.max((area / max_cells as f32).sqrt());
+        loop {
+            self.cols = ((bounds.size.x / self.cell_size).ceil() as usize).max(1);
+            self.rows = ((bounds.size.y / self.cell_size).ceil() as usize).max(1);
+            // Rounding up can still leave a few too many
+            if self.cols.saturating_mul(self.rows) <= max_cells {
+                break;
+            }
+            self.cell_size *= 1.1;
+        }
+
+        // Counting sort of the objects by cell
+        self.cell_start.clear();
+        self.cell_start.resize(self.cols * self.rows + 1, 0);
+        self.object_cells.clear();
+        for object in objects {
+            let cell = self.cell_index(object.get_position());
+            self.object_cells.push(cell);
+            self.cell_start[cell + 1] += 1;
+        }
+        for cell in 1..self.cell_start.len() {
+            self.cell_start[cell] += self.cell_start[cell - 1];
+        }
+        self.cell_objects.resize(objects.len(), 0);
+        // Fill back to front so each cell ends up in ascending object order
+        for (object, &cell) in self.object_cells.iter().enumerate().rev() {
+            self.cell_start[cell + 1] -= 1;
+            self.cell_objects[self.cell_start[cell + 1]] = object;
+        }
+        // cell_start is now shifted by one cell, put it back
+        self.cell_start.rotate_left(1);
+        let last = self.cell_start.len() - 1;
+        self.cell_start[last] = objects.len();
+    }
+
+    fn cell_index(&self, position: Vec2) -> usize {
+        let cell = (position - self.origin) / self.cell_size;
+        // Float to int casts saturate, so negative (and NaN) positions land in cell 0
+        let col = (cell.x as usize).min(self.cols - 1);
+        let row = (cell.y as usize).min(self.rows - 1);
+        col * self.rows + row
+    }
+
+    pub fn cell(&self, col: usize, row: usize) -> &[usize] {
+        let cell = col * self.rows + row;
+        &self.cell_objects[self.cell_start[cell]..self.cell_start[cell + 1]]
+    }
+
+    /// Call `f` once for every pair of objects in the same or neighbouring cells
+    /// whose first object lies in column `col`.
+    pub fn for_each_pair_in_column(&self, col: usize, mut f: impl FnMut(usize, usize)) {
+        for row in 0..self.rows {
+            let cell = self.cell(col, row);
+            for (k, &i) in cell.iter().enumerate() {
+                for &j in &cell[k + 1..] {
+                    f(i, j);
+                }
+                // Only look forward (half of the 3x3 neighbourhood) so every
+                // pair of cells is visited once
+                for (d_col, d_row) in [(0, 1), (1, -1), (1, 0), (1, 1)] {
+                    let n_col = col as isize + d_col;
+                    let n_row = row as isize + d_row;
+                    if n_col >= self.cols as isize || n_row < 0 || n_row >= self.rows as isize {
+                        continue;
+                    }
+                    for &j in self.cell(n_col as usize, n_row as usize) {
+                        f(i, j);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Call `f` once for every candidate pair in the grid.
+    pub fn for_each_pair(&self, mut f: impl FnMut(usize, usize)) {
+        for col in 0..self.cols {
+            self.for_each_pair_in_column(col, &mut f);
+        }
+    }
+
+    pub fn solve_collisions(&self, objects: &mut [VerletObject]) {
+        self.for_each_pair(|i, j| resolve_pair_in(objects, i, j));
+    }
+
+    /// Same as [`CollisionGrid::solve_collisions`], spread over the current rayon pool.
+    ///
+    /// The columns are cut into two strips per thread. A strip only reaches one
+    /// column past its right edge, so all even strips can be solved at the same
+    /// time without two threads touching the same object, then all odd strips.
+    pub fn solve_collisions_parallel(&self, objects: &mut [VerletObject]) {
+        assert_eq!(objects.len(), self.cell_objects.len(), "grid is stale");
+        let strip_width = self.cols.div_ceil(2 * rayon::current_num_threads());
+        let strip_count = self.cols.div_ceil(strip_width);
+        let shared = SharedObjects(objects.as_mut_ptr());
+        for parity in 0..2 {
+            (parity..strip_count)
+                .into_par_iter()
+                .step_by(2)
+                .for_each(|strip| {
+                    let first_col = strip * strip_width;
+                    let last_col = (first_col + strip_width).min(self.cols);
+                    for col in first_col..last_col {
+                        self.for_each_pair_in_column(col, |i, j| {
+                            // SAFETY: pairs are distinct objects from this grid, and no
+                            // other strip in this pass reaches either of them.
+                            let (a, b) = unsafe { shared.pair(i, j) };
+                            resolve_pair(a, b);
+                        });
+                    }
+                });
+        }
+    }
+}
+
+impl Broadphase for CollisionGrid {
+    fn rebuild(&mut self, objects: &[VerletObject], bounds: &WorldBounds) {
+        self.rebuild_with_cell_size(objects, bounds, CollisionGrid::cell_size_for(objects));
+    }
+
+    fn for_each_candidate_pair(&self, f: &mut dyn FnMut(usize, usize)) {
+        self.for_each_pair(f);
+    }
+
+    fn solve_collisions(&self, objects: &mut [VerletObject]) {
+        CollisionGrid::solve_collisions(self, objects);
+    }
+
+    fn solve_collisions_parallel(&self, objects: &mut [VerletObject]) {
+        CollisionGrid::solve_collisions_parallel(self, objects);
+    }
+}
@@ -0,0 +1,147 @@
+//! Collision response and the broadphases that find the pairs to test.
+
+mod grid;
+mod spatial_hash;
+
+use std::fmt::Debug;
+
+pub use grid::CollisionGrid;
+pub use spatial_hash::SpatialHash;
+
+use crate::{VerletObject, WorldBounds};
+
+/// Push two overlapping objects apart along the axis between their centres,
+/// moving each in proportion to its inverse mass. With a restitution in their
+/// materials, the approaching part of their relative velocity is then reflected,
+/// and friction is applied to the sliding part.
+fn resolve_pair(a: &mut VerletObject, b: &mut VerletObject) {
+    let inverse_mass_a = a.effective_inverse_mass();
+    let inverse_mass_b = b.effective_inverse_mass();
+    let total_inverse_mass = inverse_mass_a + inverse_mass_b;
+    if total_inverse_mass == 0.0 || !a.collides_with(b) {
+        // Two static objects, or filtered out
+        return;
+    }
+    let collision_axis = a.get_position() - b.get_position();
+    let distance: f32 = collision_axis.length();
+    let min_distance = a.radius + b.radius;
+    if distance < min_distance && distance > 0.0 {
+        // Collision detected
+        let n = collision_axis / distance;
+        let delta: f32 = min_distance - distance;
+        let weight_a = inverse_mass_a / total_inverse_mass;
+        let weight_b = inverse_mass_b / total_inverse_mass;
+        let velocity_a = a.get_velocity();
+        let velocity_b = b.get_velocity();
+        a.position_current += weight_a * delta * n;
+        b.position_current -= weight_b * delta * n;
+
+        let material = a.material.combine(b.material);
+        if let Some(restitution) = material.restitution {
+            let approach = (velocity_a - velocity_b).dot(n);
+            if approach < 0.0 {
+                // Replace the velocity the push gave with the reflected one,
+                // split by mass like an impulse
+                let change = -(1.0 + restitution) * approach * n;
+                a.position_old = a.position_current - (velocity_a + weight_a * change);
+                b.position_old = b.position_current - (velocity_b - weight_b * change);
+            }
+        }
+        if material.has_friction() {
+            let relative = a.get_velocity() - b.get_velocity();
+            let slip = relative - relative.dot(n) * n;
+            let friction = material.friction(slip, delta);
+            a.position_current -= weight_a * friction;
+            b.position_current += weight_b * friction;
+        }
+    }
+}
+
+fn resolve_pair_in(objects: &mut [VerletObject], i: usize, j: usize) {
+    debug_assert_ne!(i, j);
+    let (low, high) = objects.split_at_mut(i.max(j));
+    if i < j {
+        resolve_pair(&mut low[i], &mut high[0]);
+    } else {
+        resolve_pair(&mut high[0], &mut low[j]);
+    }
+}
+
+/// Finds candidate pairs of objects that might be touching.
+///
+/// Rebuilt by the solver every substep before the collisions are solved. Every
+/// touching pair must be reported exactly once; pairs that turn out not to touch
+/// are simply skipped.
+pub trait Broadphase: Debug + Send + Sync {
+    /// Re-bucket every object. Must be called whenever objects have moved.
+    fn rebuild(&mut self, objects: &[VerletObject], bounds: &WorldBounds);
+
+    /// Call `f` once for every candidate pair.
+    fn for_each_candidate_pair(&self, f: &mut dyn FnMut(usize, usize));
+
+    /// Push apart every overlapping pair, in a fixed order.
+    fn solve_collisions(&self, objects: &mut [VerletObject]) {
+        self.for_each_candidate_pair(&mut |i, j| resolve_pair_in(objects, i, j));
+    }
+
+    /// Like [`Broadphase::solve_collisions`], spread over the current rayon pool
+    /// where the broadphase supports it.
+    fn solve_collisions_parallel(&self, objects: &mut [VerletObject]) {
+        self.solve_collisions(objects);
+    }
+}
+
+/// Rebuild `broadphase` and push apart every overlapping pair it finds.
+pub fn solve_collisions<B: Broadphase + ?Sized>(
+    broadphase: &mut B,
+    objects: &mut [VerletObject],
+    bounds: &WorldBounds,
+    parallel: bool,
+) {
+    broadphase.rebuild(objects, bounds);
+    if parallel {
+        broadphase.solve_collisions_parallel(objects);
+    } else {
+        broadphase.solve_collisions(objects);
+    }
+}
+
+/// Brute force O(n^2) collision detection
+pub fn solve_collisions_brute_force(objects: &mut [VerletObject]) {
+    let object_count = objects.len();
+    for i in 0..object_count {
+        for j in i + 1..object_count {
+            resolve_pair_in(objects, i, j);
+        }
+    }
+}
+
+/// Tests every pair, O(n^2). Only for small scenes and as a reference.
+#[derive(Debug, Default)]
+pub struct BruteForce {
+    object_count: usize,
+}
+
+impl BruteForce {
+    pub fn new() -> Self {
+        BruteForce::default()
+    }
+}
+
+impl Broadphase for BruteForce {
+    fn rebuild(&mut self, objects: &[VerletObject], _bounds: &WorldBounds) {
+        self.object_count = objects.len();
+    }
+
+    fn for_each_candidate_pair(&self, f: &mut dyn FnMut(usize, usize)) {
+        for i in 0..self.object_count {
+            for j in i + 1..self.object_count {
+                f(i, j);
+            }
+        }
+    }
+
+    fn solve_collisions(&self, objects: &mut [VerletObject]) {
+        solve_collisions_brute_force(objects);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use super::{resolve_pair_in, Broadphase, CollisionGrid};
+use crate::{VerletObject, WorldBounds};
+
+type CellKey = (i32, i32);
+
+/// Sparse grid keyed by cell coordinates, for unbounded worlds.
+///
+/// Only occupied cells are stored, so objects can spread arbitrarily far
+/// without the memory cost of a dense grid covering all of them. Like
+/// [`CollisionGrid`], cells are as wide as the largest diameter.
+#[derive(Debug, Default)]
+pub struct SpatialHash {
+    cell_size: f32,
+    // Every object with its cell, sorted by cell and then object
+    entries: Vec<(CellKey, usize)>,
+    // Occupied cells in sorted order, with their range in `entries`
+    cells: Vec<(CellKey, usize, usize)>,
+    // Index into `cells`
+    lookup: HashMap<CellKey, usize>,
+}
+
+impl SpatialHash {
+    pub fn new() -> Self {
+        SpatialHash::default()
+    }
+
+    /// Number of occupied cells.
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Re-bucket every object into cells of `cell_size`, which must be at least
+    /// [`CollisionGrid::cell_size_for`] to find every contact.
+    pub fn rebuild_with_cell_size(&mut self, objects: &[VerletObject], cell_size: f32) {
+        // An empty scene or zero-sized objects would give zero-sized cells
+        self.cell_size = if cell_size > 0.0 { cell_size } else { 1.0 };
+
+        self.entries.clear();
+        for (index, object) in objects.iter().enumerate() {
+            let cell = (object.get_position() / self.cell_size).floor();
+            // Float to int casts saturate, so far away (and NaN) positions still land somewhere
+            self.entries.push(((cell.x as i32, cell.y as i32), index));
+        }
+        self.entries.sort_unstable();
+
+        self.cells.clear();
+        self.lookup.clear();
+        let mut start = 0;
+        while start < self.entries.len() {
+            let key = self.entries[start].0;
+            let end = start
+                + self.entries[start..]
+                    .iter()
+                    .take_while(|(cell, _)| *cell == key)
+                    .count();
+            self.lookup.insert(key, self.cells.len());
+            self.cells.push((key, start, end));
+            start = end;
+        }
+    }
+
+    fn cell(&self, key: CellKey) -> Option<&[(CellKey, usize)]> {
+        let &(_, start, end) = &self.cells[*self.lookup.get(&key)?];
+        Some(&self.entries[start..end])
+    }
+
+    /// Call `f` once for every pair of objects in the same or neighbouring cells.
+    pub fn for_each_pair(&self, mut f: impl FnMut(usize, usize)) {
+        for &((col, row), start, end) in &self.cells {
+            let cell = &self.entries[start..end];
+            // Same half neighbourhood as the dense grid, looked up once per cell
+            let neighbours = [(0, 1), (1, -1), (1, 0), (1, 1)].map(|(d_col, d_row)| {
+                match (col.checked_add(d_col), row.checked_add(d_row)) {
+                    (Some(n_col), Some(n_row)) => self.cell((n_col, n_row)).unwrap_or_default(),
+                    _ => &[],
+                }
+            });
+            for (k, &(_, i)) in cell.iter().enumerate() {
+                for &(_, j) in &cell[k + 1..] {
+                    f(i, j);
+                }
+                for neighbour in neighbours {
+                    for &(_, j) in neighbour {
+                        f(i, j);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn solve_collisions(&self, objects: &mut [VerletObject]) {
+        self.for_each_pair(|i, j| resolve_pair_in(objects, i, j));
+    }
+}
+
+impl Broadphase for SpatialHash {
+    fn rebuild(&mut self, objects: &[VerletObject], _bounds: &WorldBounds) {
+        self.rebuild_with_cell_size(objects, CollisionGrid::cell_size_for(objects));
+    }
+
+    fn for_each_candidate_pair(&self, f: &mut dyn FnMut(usize, usize)) {
+        self.for_each_pair(f);
+    }
+
+    fn solve_collisions(&self, objects: &mut [VerletObject]) {
+        SpatialHash::solve_collisions(self, objects);
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::VerletObject;
+
+/// Keeps two objects at `rest_length` from each other, like a stick.
+///
+/// `a` and `b` are indices into the objects slice passed to `Solver::update`.
+/// A `stiffness` of 1 fully corrects the distance every substep, lower values
+/// make the link springy.
+///
+/// A constraint with a `max_strain` or `max_force` breaks when either is
+/// exceeded: the solver removes it and reports a [`ConstraintBroken`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DistanceConstraint {
+    pub a: usize,
+    pub b: usize,
+    pub rest_length: f32,
+    pub stiffness: f32,
+    /// Largest stretch beyond the rest length, as a fraction of it. Being
+    /// squashed never breaks a constraint on strain.
+    #[serde(default)]
+    pub max_strain: Option<f32>,
+    /// Largest force, pulling or pushing, the constraint applies to hold its length.
+    #[serde(default)]
+    pub max_force: Option<f32>,
+}
+
+/// A breakable constraint gave way, see [`crate::Solver::drain_broken_constraints`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstraintBroken {
+    pub a: usize,
+    pub b: usize,
+    /// [`crate::Solver::step_count`] when it broke: at the end of the update it
+    /// tore in, or the current one if it was broken between updates, e.g. cut.
+    pub step: u64,
+}
+
+impl DistanceConstraint {
+    pub fn new(a: usize, b: usize, rest_length: f32, stiffness: f32) -> Self {
+        DistanceConstraint {
+            a,
+            b,
+            rest_length,
+            stiffness,
+            max_strain: None,
+            max_force: None,
+        }
+    }
+
+    pub fn with_max_strain(mut self, max_strain: f32) -> Self {
+        self.max_strain = Some(max_strain);
+        self
+    }
+
+    pub fn with_max_force(mut self, max_force: f32) -> Self {
+        self.max_force = Some(max_force);
+        self
+    }
+
+    /// Constraint that keeps `a` and `b` at their current distance.
+    pub fn between(objects: &[VerletObject], a: usize, b: usize, stiffness: f32) -> Self {
+        let rest_length = objects[a]
+            .get_position()
+            .distance(objects[b].get_position());
+        DistanceConstraint::new(a, b, rest_length, stiffness)
+    }
+
+    /// Current length divided by the rest length.
+    pub fn stretch(&self, objects: &[VerletObject]) -> f32 {
+        let length = objects[self.a]
+            .get_position()
+            .distance(objects[self.b].get_position());
+        length / self.rest_length
+    }
+
+    /// Pull the objects towards the rest length over a substep of `dt`.
+    /// Returns false instead if the constraint breaks.
+    pub(crate) fn relax(&self, objects: &mut [VerletObject], dt: f32) -> bool {
+        if self.a == self.b || self.a >= objects.len() || self.b >= objects.len() {
+            return true;
+        }
+        let inverse_mass_a = objects[self.a].effective_inverse_mass();
+        let inverse_mass_b = objects[self.b].effective_inverse_mass();
+        let total_inverse_mass = inverse_mass_a + inverse_mass_b;
+        let axis = objects[self.a].get_position() - objects[self.b].get_position();
+        let distance = axis.length();
+        if let Some(max_strain) = self.max_strain {
+            if distance - self.rest_length > max_strain * self.rest_length {
+                return false;
+            }
+        }
+        if total_inverse_mass == 0.0 || distance == 0.0 {
+            return true;
+        }
+        let n = axis / distance;
+        let correction = self.stiffness * (distance - self.rest_length) * n;
+        if let Some(max_force) = self.max_force {
+            // The force that moves the objects by the correction within the substep
+            let force = correction.length() / (total_inverse_mass * dt * dt);
+            if force > max_force {
+                return false;
+            }
+        }
+        objects[self.a].position_current -= inverse_mass_a / total_inverse_mass * correction;
+        objects[self.b].position_current += inverse_mass_b / total_inverse_mass * correction;
+        true
+    }
+}
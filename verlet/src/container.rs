@@ -0,0 +1,164 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::WorldBounds;
+
+/// Gap kept between objects and the container walls, half the width of the
+/// border the demo draws.
+const BORDER: f32 = 1.0;
+
+/// Shape the objects are kept in by `Solver::apply_constraints`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Container {
+    /// Inside the world bounds.
+    #[default]
+    Box,
+    /// Inside a circle, like a bowl.
+    Circle { center: Vec2, radius: f32 },
+    /// Inside the world bounds but outside a circle, which acts as an obstacle.
+    InvertedCircle { center: Vec2, radius: f32 },
+}
+
+/// A wall objects can be pushed back by. The world bounds have four, with
+/// `Top` at the smallest y; the circle of a circular container is one more.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Wall {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Circle,
+}
+
+/// Restitution of each wall, from 0 (sticky) to 1 (elastic).
+///
+/// `None` keeps the plain clamp, which leaves whatever velocity the integrator
+/// ends up with and bounces a little depending on the substeps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WallRestitution {
+    pub left: Option<f32>,
+    pub right: Option<f32>,
+    pub top: Option<f32>,
+    pub bottom: Option<f32>,
+    pub circle: Option<f32>,
+}
+
+impl WallRestitution {
+    /// The same restitution on every wall.
+    pub fn uniform(restitution: f32) -> Self {
+        let restitution = Some(restitution);
+        WallRestitution {
+            left: restitution,
+            right: restitution,
+            top: restitution,
+            bottom: restitution,
+            circle: restitution,
+        }
+    }
+
+    pub fn get(&self, wall: Wall) -> Option<f32> {
+        match wall {
+            Wall::Left => self.left,
+            Wall::Right => self.right,
+            Wall::Top => self.top,
+            Wall::Bottom => self.bottom,
+            Wall::Circle => self.circle,
+        }
+    }
+
+    pub fn set(&mut self, wall: Wall, restitution: Option<f32>) {
+        let slot = match wall {
+            Wall::Left => &mut self.left,
+            Wall::Right => &mut self.right,
+            Wall::Top => &mut self.top,
+            Wall::Bottom => &mut self.bottom,
+            Wall::Circle => &mut self.circle,
+        };
+        *slot = restitution;
+    }
+}
+
+/// Report the bounds walls crossed on the way from `from` to the clamped `to`.
+fn bounds_walls(from: Vec2, to: Vec2, hit: &mut impl FnMut(Wall, Vec2)) {
+    let correction = to - from;
+    if correction.x > 0.0 {
+        hit(Wall::Left, Vec2::X);
+    } else if correction.x < 0.0 {
+        hit(Wall::Right, -Vec2::X);
+    }
+    if correction.y > 0.0 {
+        hit(Wall::Top, Vec2::Y);
+    } else if correction.y < 0.0 {
+        hit(Wall::Bottom, -Vec2::Y);
+    }
+}
+
+/// Closest position to `position` outside the circle for an object of `object_radius`.
+fn push_out_of_circle(center: Vec2, radius: f32, position: Vec2, object_radius: f32) -> Vec2 {
+    let min_distance = radius + object_radius + BORDER;
+    let offset = position - center;
+    let distance = offset.length();
+    if distance >= min_distance {
+        position
+    } else if distance > 0.0 {
+        center + offset / distance * min_distance
+    } else {
+        // Dead centre, any direction will do
+        center - Vec2::Y * min_distance
+    }
+}
+
+impl Container {
+    /// Closest position to `position` where an object of `object_radius` fits.
+    pub fn constrain(&self, bounds: &WorldBounds, position: Vec2, object_radius: f32) -> Vec2 {
+        match *self {
+            Container::Box => bounds.clamp(position, object_radius + BORDER),
+            Container::Circle { center, radius } => {
+                let max_distance = (radius - object_radius - BORDER).max(0.0);
+                let offset = position - center;
+                let distance = offset.length();
+                if distance > max_distance {
+                    center + offset / distance * max_distance
+                } else {
+                    position
+                }
+            }
+            Container::InvertedCircle { center, radius } => {
+                let position = bounds.clamp(position, object_radius + BORDER);
+                let pushed = push_out_of_circle(center, radius, position, object_radius);
+                // Near an edge the push can leave the bounds, which win
+                bounds.clamp(pushed, object_radius + BORDER)
+            }
+        }
+    }
+
+    /// [`Container::constrain`], also calling `hit` with every wall that pushed
+    /// the object and the wall's normal, pointing back inside.
+    pub(crate) fn constrain_with_walls(
+        &self,
+        bounds: &WorldBounds,
+        position: Vec2,
+        object_radius: f32,
+        mut hit: impl FnMut(Wall, Vec2),
+    ) -> Vec2 {
+        let constrained = self.constrain(bounds, position, object_radius);
+        match *self {
+            Container::Box => bounds_walls(position, constrained, &mut hit),
+            Container::Circle { .. } => {
+                if constrained != position {
+                    hit(Wall::Circle, (constrained - position).normalize_or_zero());
+                }
+            }
+            Container::InvertedCircle { center, radius } => {
+                let clamped = bounds.clamp(position, object_radius + BORDER);
+                bounds_walls(position, clamped, &mut hit);
+                let pushed = push_out_of_circle(center, radius, clamped, object_radius);
+                if pushed != clamped {
+                    hit(Wall::Circle, (pushed - clamped).normalize_or_zero());
+                }
+                bounds_walls(pushed, constrained, &mut hit);
+            }
+        }
+        constrained
+    }
+}
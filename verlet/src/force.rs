@@ -0,0 +1,319 @@
+use std::fmt::Debug;
+
+use glam::Vec2;
+
+use crate::VerletObject;
+
+/// A force field evaluated every substep, on top of the solver's gravity.
+///
+/// Generators are registered with `Solver::add_force_generator`. They are not
+/// part of saved scenes or snapshots.
+pub trait ForceGenerator: Debug + Send + Sync {
+    /// Accelerate `objects` for the coming substep. `time` is the simulation time
+    /// in seconds and `dt` the substep length, to turn the implicit Verlet
+    /// displacement into a velocity.
+    fn apply(&mut self, objects: &mut [VerletObject], time: f64, dt: f32);
+
+    /// Finished generators are removed by the solver.
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+/// Handle returned by `Solver::add_force_generator`, to remove it again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ForceGeneratorId(pub(crate) u64);
+
+/// Weight falling linearly from 1 at `center` to 0 at `radius`.
+fn falloff(offset: Vec2, radius: f32) -> f32 {
+    (1.0 - offset.length() / radius).max(0.0)
+}
+
+/// Pulls objects within `radius` towards `center`, or pushes them away when
+/// `strength` is negative. Acts like gravity, regardless of mass.
+#[derive(Clone, Copy, Debug)]
+pub struct Attractor {
+    pub center: Vec2,
+    pub radius: f32,
+    pub strength: f32,
+}
+
+impl Attractor {
+    pub fn new(center: Vec2, radius: f32, strength: f32) -> Self {
+        Attractor {
+            center,
+            radius,
+            strength,
+        }
+    }
+
+    pub fn repulsor(center: Vec2, radius: f32, strength: f32) -> Self {
+        Attractor::new(center, radius, -strength)
+    }
+}
+
+impl ForceGenerator for Attractor {
+    fn apply(&mut self, objects: &mut [VerletObject], _time: f64, _dt: f32) {
+        for object in objects.iter_mut() {
+            let offset = self.center - object.get_position();
+            let weight = falloff(offset, self.radius);
+            if weight > 0.0 {
+                object.accelerate(offset.normalize_or_zero() * self.strength * weight);
+            }
+        }
+    }
+}
+
+/// Short radial blast: pushes objects within `radius` away from `center` for
+/// `duration` seconds after it is first applied, then finishes.
+#[derive(Clone, Copy, Debug)]
+pub struct Explosion {
+    pub center: Vec2,
+    pub radius: f32,
+    /// Force at the centre, split by mass.
+    pub strength: f32,
+    pub duration: f64,
+    start: Option<f64>,
+    finished: bool,
+}
+
+impl Explosion {
+    pub fn new(center: Vec2, radius: f32, strength: f32, duration: f64) -> Self {
+        Explosion {
+            center,
+            radius,
+            strength,
+            duration,
+            start: None,
+            finished: false,
+        }
+    }
+}
+
+impl ForceGenerator for Explosion {
+    fn apply(&mut self, objects: &mut [VerletObject], time: f64, _dt: f32) {
+        let start = *self.start.get_or_insert(time);
+        if time - start >= self.duration {
+            self.finished = true;
+            return;
+        }
+        for object in objects.iter_mut() {
+            let offset = object.get_position() - self.center;
+            let weight = falloff(offset, self.radius);
+            if weight > 0.0 {
+                object.apply_force(offset.normalize_or_zero() * self.strength * weight);
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Swirls objects within `radius` around `center`, counter-clockwise on screen
+/// for a positive `strength`. Acts like gravity, regardless of mass.
+#[derive(Clone, Copy, Debug)]
+pub struct Vortex {
+    pub center: Vec2,
+    pub radius: f32,
+    pub strength: f32,
+}
+
+impl Vortex {
+    pub fn new(center: Vec2, radius: f32, strength: f32) -> Self {
+        Vortex {
+            center,
+            radius,
+            strength,
+        }
+    }
+}
+
+impl ForceGenerator for Vortex {
+    fn apply(&mut self, objects: &mut [VerletObject], _time: f64, _dt: f32) {
+        for object in objects.iter_mut() {
+            let offset = object.get_position() - self.center;
+            let weight = falloff(offset, self.radius);
+            if weight > 0.0 {
+                // perp turns counter-clockwise with y pointing up, so clockwise on screen
+                let tangent = -offset.perp().normalize_or_zero();
+                object.accelerate(tangent * self.strength * weight);
+            }
+        }
+    }
+}
+
+/// Force of `-coefficient * velocity` on every object.
+#[derive(Clone, Copy, Debug)]
+pub struct LinearDrag {
+    pub coefficient: f32,
+}
+
+impl LinearDrag {
+    pub fn new(coefficient: f32) -> Self {
+        LinearDrag { coefficient }
+    }
+}
+
+impl ForceGenerator for LinearDrag {
+    fn apply(&mut self, objects: &mut [VerletObject], _time: f64, dt: f32) {
+        for object in objects.iter_mut() {
+            let velocity = object.get_velocity() / dt;
+            object.apply_force(-self.coefficient * velocity);
+        }
+    }
+}
+
+/// Gas pressure inside a closed loop of objects, pushing every edge of the
+/// loop outward with `pressure * (target_area - area) / target_area` per unit
+/// of its length. A loop squashed below its target area is pushed back out,
+/// one blown up beyond it is pulled back in.
+///
+/// `indices` are the objects around the loop, in order, either way round.
+/// See [`crate::SoftBody`] to build the loop and its pressure together.
+#[derive(Clone, Debug)]
+pub struct Pressure {
+    pub indices: Vec<usize>,
+    pub target_area: f32,
+    pub pressure: f32,
+}
+
+impl Pressure {
+    pub fn new(indices: Vec<usize>, target_area: f32, pressure: f32) -> Self {
+        Pressure {
+            indices,
+            target_area,
+            pressure,
+        }
+    }
+
+    /// Area enclosed by the loop.
+    pub fn area(&self, objects: &[VerletObject]) -> f32 {
+        signed_area(&self.indices, objects).abs()
+    }
+}
+
+/// Shoelace area of the loop through `indices`, positive when it runs
+/// clockwise on screen.
+pub(crate) fn signed_area(indices: &[usize], objects: &[VerletObject]) -> f32 {
+    let mut twice_area = 0.0;
+    for (i, &a) in indices.iter().enumerate() {
+        let b = indices[(i + 1) % indices.len()];
+        twice_area += objects[a]
+            .get_position()
+            .perp_dot(objects[b].get_position());
+    }
+    twice_area / 2.0
+}
+
+impl ForceGenerator for Pressure {
+    fn apply(&mut self, objects: &mut [VerletObject], _time: f64, _dt: f32) {
+        // Removed objects take the loop with them
+        if self.indices.len() < 3
+            || self.target_area <= 0.0
+            || self.indices.iter().any(|&index| index >= objects.len())
+        {
+            return;
+        }
+        let area = signed_area(&self.indices, objects);
+        let pressure = self.pressure * (self.target_area - area.abs()) / self.target_area;
+        for (i, &a) in self.indices.iter().enumerate() {
+            let b = self.indices[(i + 1) % self.indices.len()];
+            let edge = objects[b].get_position() - objects[a].get_position();
+            // As long as the edge, pointing out of the loop whichever way it runs
+            let outward = -edge.perp() * area.signum();
+            let force = outward * pressure / 2.0;
+            objects[a].apply_force(force);
+            objects[b].apply_force(force);
+        }
+    }
+}
+
+/// Drags objects along with a gusty wind.
+///
+/// The force is `coefficient * (wind - velocity)`, where the wind velocity
+/// varies smoothly in space and time with value noise: `turbulence` is the
+/// size of the gusts relative to `velocity`, `scale` their size in world units.
+#[derive(Clone, Copy, Debug)]
+pub struct Wind {
+    pub velocity: Vec2,
+    pub coefficient: f32,
+    pub turbulence: f32,
+    pub scale: f32,
+    pub seed: u32,
+}
+
+impl Wind {
+    pub fn new(velocity: Vec2, coefficient: f32) -> Self {
+        Wind {
+            velocity,
+            coefficient,
+            turbulence: 0.5,
+            scale: 200.0,
+            seed: 0,
+        }
+    }
+
+    pub fn with_turbulence(mut self, turbulence: f32, scale: f32, seed: u32) -> Self {
+        self.turbulence = turbulence;
+        self.scale = scale;
+        self.seed = seed;
+        self
+    }
+
+    /// Wind velocity at `position` and `time`.
+    pub fn sample(&self, position: Vec2, time: f64) -> Vec2 {
+        let p = position / self.scale;
+        // Gusts change about once a second
+        let t = time as f32;
+        let gust = Vec2::new(
+            value_noise(p.x, p.y, t, self.seed),
+            value_noise(p.x, p.y, t, self.seed.wrapping_add(1)),
+        );
+        self.velocity + gust * self.velocity.length() * self.turbulence
+    }
+}
+
+impl ForceGenerator for Wind {
+    fn apply(&mut self, objects: &mut [VerletObject], time: f64, dt: f32) {
+        for object in objects.iter_mut() {
+            let velocity = object.get_velocity() / dt;
+            let wind = self.sample(object.get_position(), time);
+            object.apply_force(self.coefficient * (wind - velocity));
+        }
+    }
+}
+
+/// Pseudo-random value in `[-1, 1]` for a lattice point.
+fn lattice(x: i32, y: i32, z: i32, seed: u32) -> f32 {
+    let mut hash = seed
+        ^ (x as u32).wrapping_mul(0x8da6b343)
+        ^ (y as u32).wrapping_mul(0xd8163841)
+        ^ (z as u32).wrapping_mul(0xcb1ab31f);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x7feb352d);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x846ca68b);
+    hash ^= hash >> 16;
+    hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// Smoothly interpolated 3D value noise in `[-1, 1]`.
+fn value_noise(x: f32, y: f32, z: f32, seed: u32) -> f32 {
+    let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (tx, ty, tz) = (smooth(x - x0), smooth(y - y0), smooth(z - z0));
+    let (x0, y0, z0) = (x0 as i32, y0 as i32, z0 as i32);
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let plane = |z: i32| {
+        let top = lerp(lattice(x0, y0, z, seed), lattice(x0 + 1, y0, z, seed), tx);
+        let bottom = lerp(
+            lattice(x0, y0 + 1, z, seed),
+            lattice(x0 + 1, y0 + 1, z, seed),
+            tx,
+        );
+        lerp(top, bottom, ty)
+    };
+    lerp(plane(z0), plane(z0 + 1), tz)
+}
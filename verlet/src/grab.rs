@@ -0,0 +1,37 @@
+use glam::Vec2;
+
+use crate::VerletObject;
+
+/// An object dragged toward a target by a hard position constraint.
+///
+/// Each update moves the object from the previous target to the new one over
+/// its substeps, so the implicit velocity is the drag velocity and the object
+/// keeps it when released.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Grab {
+    pub(crate) index: usize,
+    pub(crate) target: Vec2,
+    from: Vec2,
+}
+
+impl Grab {
+    pub(crate) fn new(index: usize, position: Vec2) -> Self {
+        Grab {
+            index,
+            target: position,
+            from: position,
+        }
+    }
+
+    /// Place the object `t` of the way from the previous target to the new one.
+    pub(crate) fn apply(&self, objects: &mut [VerletObject], t: f32) {
+        if let Some(object) = objects.get_mut(self.index) {
+            object.position_current = self.from.lerp(self.target, t);
+        }
+    }
+
+    /// The current target becomes the starting point of the next update.
+    pub(crate) fn finish_update(&mut self) {
+        self.from = self.target;
+    }
+}
@@ -0,0 +1,38 @@
+//! Headless Verlet integration core.
+//!
+//! Nothing in this crate touches a window or a renderer, so the solver can be
+//! embedded in servers, tests and batch jobs. The interactive demo lives in the
+//! `verlet-rs` binary on top of it.
+
+mod bounds;
+mod cloth;
+pub mod collision;
+mod constraint;
+mod container;
+pub mod force;
+mod grab;
+mod material;
+mod object;
+mod rope;
+mod scene;
+mod snapshot;
+mod soft_body;
+mod solver;
+mod timestep;
+
+pub use bounds::WorldBounds;
+pub use cloth::{Cloth, ClothMesh, ClothTriangle};
+pub use constraint::{ConstraintBroken, DistanceConstraint};
+pub use container::{Container, Wall, WallRestitution};
+pub use glam::Vec2;
+pub use material::Material;
+pub use object::{nearest_object, VerletObject};
+pub use rope::Rope;
+pub use scene::{Scene, SceneError, SCENE_VERSION};
+pub use snapshot::{Snapshot, SnapshotError, SNAPSHOT_MAGIC, SNAPSHOT_VERSION};
+pub use soft_body::{SoftBody, SoftBodyHandle};
+pub use solver::{DebugTimeInfo, Solver, StepReport};
+pub use timestep::FixedTimestep;
+
+/// Radius given to objects created with [`VerletObject::new`].
+pub const DEFAULT_RADIUS: f32 = 3.0;
@@ -0,0 +1,73 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Surface properties of an object, or of the container walls.
+///
+/// When two surfaces touch, their coefficients are combined with the geometric
+/// mean, so a frictionless surface stays frictionless against anything.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    /// Contacts stick while the sliding displacement is below
+    /// `static_friction` times the penetration that was corrected.
+    pub static_friction: f32,
+    /// Otherwise the slide is cut by `kinetic_friction` times the penetration.
+    pub kinetic_friction: f32,
+    /// Bounciness of contacts between objects, from 0 (sticky) to 1 (elastic).
+    /// `None` keeps the plain positional push apart. If only one side has a
+    /// restitution, that one is used.
+    #[serde(default)]
+    pub restitution: Option<f32>,
+}
+
+impl Material {
+    pub const FRICTIONLESS: Material = Material {
+        static_friction: 0.0,
+        kinetic_friction: 0.0,
+        restitution: None,
+    };
+
+    pub fn new(static_friction: f32, kinetic_friction: f32) -> Self {
+        Material {
+            static_friction,
+            kinetic_friction,
+            restitution: None,
+        }
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = Some(restitution);
+        self
+    }
+
+    /// Material of a contact between the two surfaces.
+    pub(crate) fn combine(self, other: Material) -> Material {
+        Material {
+            static_friction: (self.static_friction * other.static_friction).sqrt(),
+            kinetic_friction: (self.kinetic_friction * other.kinetic_friction).sqrt(),
+            restitution: match (self.restitution, other.restitution) {
+                (Some(a), Some(b)) => Some((a * b).sqrt()),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+
+    pub(crate) fn has_friction(&self) -> bool {
+        self.static_friction > 0.0 || self.kinetic_friction > 0.0
+    }
+
+    /// Part of the tangential displacement `slip` that friction takes away from
+    /// a contact pushed apart by `depth`. Position based, so it works on the
+    /// implicit Verlet velocity the same way whatever the substep.
+    pub(crate) fn friction(&self, slip: Vec2, depth: f32) -> Vec2 {
+        let slip_length = slip.length();
+        if slip_length == 0.0 {
+            return Vec2::ZERO;
+        }
+        if slip_length <= self.static_friction * depth {
+            // Sticks
+            slip
+        } else {
+            slip * (self.kinetic_friction * depth / slip_length).min(1.0)
+        }
+    }
+}
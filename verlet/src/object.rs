@@ -0,0 +1,263 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{Material, DEFAULT_RADIUS};
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct VerletObject {
+    #[serde(rename = "position")]
+    pub(crate) position_current: Vec2,
+    #[serde(rename = "previous_position")]
+    pub(crate) position_old: Vec2,
+    // Where the last step started, for drawing between steps; taken from the
+    // position when a scene is applied
+    #[serde(skip)]
+    pub(crate) position_step_start: Vec2,
+    // Reset every step, no point in saving it
+    #[serde(skip)]
+    pub(crate) acceleration: Vec2,
+    pub(crate) radius: f32,
+    // 0 means infinite mass
+    pub(crate) inverse_mass: f32,
+    pub(crate) pinned: bool,
+    // Linear damping rate per second, on top of the solver's
+    #[serde(default)]
+    pub(crate) damping: f32,
+    #[serde(default)]
+    pub(crate) material: Material,
+    // 0 collides with everything
+    #[serde(default)]
+    pub(crate) collision_group: u32,
+}
+
+impl VerletObject {
+    pub fn new(position: Vec2) -> Self {
+        VerletObject {
+            position_current: position,
+            position_old: position,
+            position_step_start: position,
+            acceleration: Vec2::new(0., 0.),
+            radius: DEFAULT_RADIUS,
+            inverse_mass: 1.0,
+            pinned: false,
+            damping: 0.0,
+            material: Material::FRICTIONLESS,
+            collision_group: 0,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f32) -> Self {
+        self.set_mass(mass);
+        self
+    }
+
+    pub fn with_inverse_mass(mut self, inverse_mass: f32) -> Self {
+        self.set_inverse_mass(inverse_mass);
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    pub fn with_material(mut self, material: Material) -> Self {
+        self.material = material;
+        self
+    }
+
+    pub fn with_collision_group(mut self, group: u32) -> Self {
+        self.collision_group = group;
+        self
+    }
+
+    pub fn update_position(&mut self, dt: f32) {
+        self.update_position_damped(dt, 0.0, 0.0);
+    }
+
+    /// [`VerletObject::update_position`] with the solver's linear `damping`
+    /// rate (per second) added to the object's own, and a quadratic `drag`
+    /// force of `drag * speed²` against the motion.
+    pub(crate) fn update_position_damped(&mut self, dt: f32, damping: f32, drag: f32) {
+        if self.is_static() {
+            self.position_old = self.position_current;
+            self.acceleration = Vec2::new(0., 0.);
+            return;
+        }
+        let mut velocity = self.position_current - self.position_old;
+        let damping = damping + self.damping;
+        if damping > 0.0 {
+            // Exact decay over dt, so the loss per second does not depend on the substeps
+            velocity *= (-damping * dt).exp();
+        }
+        if drag > 0.0 {
+            // Implicit step of dv/dt = -drag * |v| * v / m, stable at any speed.
+            // |v| * dt is the displacement, so dt cancels out.
+            velocity /= 1.0 + drag * self.inverse_mass * velocity.length();
+        }
+        // Save current position
+        self.position_old = self.position_current;
+        // Perform verlet integration
+        self.position_current += velocity + self.acceleration * dt * dt;
+        // Reset acceleration
+        self.acceleration = Vec2::new(0., 0.);
+    }
+
+    pub fn accelerate(&mut self, acceleration: Vec2) {
+        self.acceleration += acceleration;
+    }
+
+    /// Accelerate by `force` divided by the mass. Static objects do not move.
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.acceleration += force * self.effective_inverse_mass();
+    }
+
+    pub fn get_position(&self) -> Vec2 {
+        self.position_current
+    }
+
+    pub fn get_old_position(&self) -> Vec2 {
+        self.position_old
+    }
+
+    pub fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    pub fn set_radius(&mut self, radius: f32) {
+        self.radius = radius;
+    }
+
+    /// Mass of the object, `f32::INFINITY` for static objects.
+    pub fn get_mass(&self) -> f32 {
+        1.0 / self.inverse_mass
+    }
+
+    /// An infinite mass makes the object static.
+    ///
+    /// # Panics
+    /// If `mass` is not greater than 0.
+    pub fn set_mass(&mut self, mass: f32) {
+        assert!(mass > 0.0, "mass must be greater than 0, got {mass}");
+        self.inverse_mass = 1.0 / mass;
+    }
+
+    pub fn get_inverse_mass(&self) -> f32 {
+        self.inverse_mass
+    }
+
+    /// 0 makes the object static. Negative values are treated as 0.
+    pub fn set_inverse_mass(&mut self, inverse_mass: f32) {
+        self.inverse_mass = inverse_mass.max(0.0);
+    }
+
+    /// Linear damping rate per second, added to [`crate::Solver::damping`].
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping;
+    }
+
+    pub fn get_material(&self) -> Material {
+        self.material
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    pub fn get_collision_group(&self) -> u32 {
+        self.collision_group
+    }
+
+    /// Objects sharing a non-zero group do not collide with each other, e.g.
+    /// the links of a rope. Group 0 collides with everything.
+    pub fn set_collision_group(&mut self, group: u32) {
+        self.collision_group = group;
+    }
+
+    /// Whether collisions between the two objects are solved at all.
+    pub(crate) fn collides_with(&self, other: &VerletObject) -> bool {
+        self.collision_group == 0 || self.collision_group != other.collision_group
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    /// A pinned object stays where it is and is immovable in collisions,
+    /// without losing its mass for when it is unpinned again.
+    pub fn set_pinned(&mut self, pinned: bool) {
+        self.pinned = pinned;
+        // Start from rest either way
+        self.position_old = self.position_current;
+    }
+
+    pub fn pin(&mut self) {
+        self.set_pinned(true);
+    }
+
+    pub fn unpin(&mut self) {
+        self.set_pinned(false);
+    }
+
+    /// Static objects (pinned or infinitely heavy) are never moved by the solver.
+    pub fn is_static(&self) -> bool {
+        self.pinned || self.inverse_mass == 0.0
+    }
+
+    /// Inverse mass as seen by the solver, 0 while pinned.
+    pub(crate) fn effective_inverse_mass(&self) -> f32 {
+        if self.pinned {
+            0.0
+        } else {
+            self.inverse_mass
+        }
+    }
+
+    /// Position `alpha` of the way through the last step, from where it started
+    /// to the current position, for drawing between fixed steps.
+    pub fn interpolated_position(&self, alpha: f32) -> Vec2 {
+        self.position_step_start.lerp(self.position_current, alpha)
+    }
+
+    /// Displacement over the last step, i.e. the implicit Verlet velocity.
+    pub fn get_velocity(&self) -> Vec2 {
+        self.position_current - self.position_old
+    }
+
+    /// Set the displacement per step by moving the previous position.
+    pub fn set_velocity(&mut self, velocity: Vec2) {
+        self.position_old = self.position_current - velocity;
+    }
+}
+
+/// A collision group none of `objects` is in yet.
+pub(crate) fn unused_collision_group(objects: &[VerletObject]) -> u32 {
+    objects
+        .iter()
+        .map(VerletObject::get_collision_group)
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+/// Index of the object whose centre is closest to `point`, if any.
+pub fn nearest_object(objects: &[VerletObject], point: Vec2) -> Option<usize> {
+    objects
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            let a = a.get_position().distance_squared(point);
+            let b = b.get_position().distance_squared(point);
+            a.total_cmp(&b)
+        })
+        .map(|(index, _)| index)
+}
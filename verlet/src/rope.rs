@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use glam::Vec2;
+
+use crate::object::unused_collision_group;
+use crate::{DistanceConstraint, Solver, VerletObject, DEFAULT_RADIUS};
+
+/// Builds a chain of objects linked by distance constraints, along a straight
+/// line or any path, so ropes do not have to be wired by hand.
+#[derive(Clone, Debug)]
+pub struct Rope {
+    path: Vec<Vec2>,
+    segments: usize,
+    segment_length: Option<f32>,
+    stiffness: f32,
+    pin_start: bool,
+    pin_end: bool,
+    self_collision: bool,
+    radius: f32,
+}
+
+impl Rope {
+    /// Straight rope from `start` to `end` made of `segments` links.
+    pub fn new(start: Vec2, end: Vec2, segments: usize) -> Self {
+        Rope::along(vec![start, end], segments)
+    }
+
+    /// Rope following the polyline `path`, with its objects spread evenly
+    /// along it.
+    pub fn along(path: Vec<Vec2>, segments: usize) -> Self {
+        Rope {
+            path,
+            segments: segments.max(1),
+            segment_length: None,
+            stiffness: 1.0,
+            pin_start: false,
+            pin_end: false,
+            self_collision: false,
+            radius: DEFAULT_RADIUS,
+        }
+    }
+
+    /// Rest length of each link. By default the links are as long as the
+    /// spacing along the path; longer links make a slack rope, shorter ones a
+    /// taut one.
+    pub fn with_segment_length(mut self, segment_length: f32) -> Self {
+        self.segment_length = Some(segment_length);
+        self
+    }
+
+    /// Stiffness of the links, see [`DistanceConstraint`].
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    pub fn pinned(mut self, start: bool, end: bool) -> Self {
+        self.pin_start = start;
+        self.pin_end = end;
+        self
+    }
+
+    /// Whether the rope's objects collide with each other. Off by default, as
+    /// neighbouring links usually overlap. They always collide with the rest
+    /// of the world.
+    pub fn with_self_collision(mut self, self_collision: bool) -> Self {
+        self.self_collision = self_collision;
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Positions of the objects, `segments + 1` of them evenly spaced along the path.
+    pub fn points(&self) -> Vec<Vec2> {
+        let path = &self.path;
+        if path.len() < 2 {
+            return path
+                .first()
+                .map_or(Vec::new(), |&point| vec![point; self.segments + 1]);
+        }
+        let piece_length = |piece: usize| path[piece].distance(path[piece + 1]);
+        let total: f32 = (0..path.len() - 1).map(piece_length).sum();
+
+        let mut points = Vec::with_capacity(self.segments + 1);
+        // Path piece the next point is on, and the path length before it
+        let mut piece = 0;
+        let mut walked = 0.0;
+        for i in 0..=self.segments {
+            let target = total * i as f32 / self.segments as f32;
+            while piece + 2 < path.len() && walked + piece_length(piece) < target {
+                walked += piece_length(piece);
+                piece += 1;
+            }
+            let length = piece_length(piece);
+            let t = if length > 0.0 {
+                ((target - walked) / length).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            points.push(path[piece].lerp(path[piece + 1], t));
+        }
+        points
+    }
+
+    /// Append the rope's objects to `objects` and its links to the solver.
+    /// Returns the indices of the new objects, from the start of the path to
+    /// its end.
+    pub fn build(&self, solver: &mut Solver, objects: &mut Vec<VerletObject>) -> Range<usize> {
+        let points = self.points();
+        let first = objects.len();
+        // A group of its own, so the links can ignore each other
+        let group = if self.self_collision {
+            0
+        } else {
+            unused_collision_group(objects)
+        };
+        objects.extend(points.iter().map(|&point| {
+            VerletObject::new(point)
+                .with_radius(self.radius)
+                .with_collision_group(group)
+        }));
+        let links = first..objects.len();
+        if links.is_empty() {
+            return links;
+        }
+
+        for a in links.start..links.end - 1 {
+            let constraint = match self.segment_length {
+                Some(length) => DistanceConstraint::new(a, a + 1, length, self.stiffness),
+                None => DistanceConstraint::between(objects, a, a + 1, self.stiffness),
+            };
+            solver.add_distance_constraint(constraint);
+        }
+        if self.pin_start {
+            objects[links.start].pin();
+        }
+        if self.pin_end {
+            objects[links.end - 1].pin();
+        }
+        links
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::path::Path;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Container, DistanceConstraint, Material, Solver, VerletObject, WallRestitution, WorldBounds,
+};
+
+/// Bumped whenever the layout of [`Scene`] changes in a way older code cannot read.
+pub const SCENE_VERSION: u32 = 1;
+
+/// Human-readable (RON) description of the objects and solver settings, for
+/// saving and sharing reproducible setups.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scene {
+    pub version: u32,
+    pub gravity: Vec2,
+    // Missing from scenes saved before damping existed
+    #[serde(default)]
+    pub damping: f32,
+    #[serde(default)]
+    pub drag: f32,
+    pub substeps: u32,
+    pub bounds: WorldBounds,
+    pub container: Container,
+    #[serde(default)]
+    pub wall_material: Material,
+    #[serde(default)]
+    pub wall_restitution: WallRestitution,
+    pub distance_constraints: Vec<DistanceConstraint>,
+    pub particles: Vec<VerletObject>,
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    UnsupportedVersion(u32),
+    /// The distance constraint at this index links an object past the particles.
+    InvalidConstraint(usize),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(error) => write!(f, "could not access scene file: {error}"),
+            SceneError::Parse(error) => write!(f, "invalid scene: {error}"),
+            SceneError::Serialize(error) => write!(f, "could not write scene: {error}"),
+            SceneError::UnsupportedVersion(version) => write!(
+                f,
+                "scene version {version} is newer than the supported version {SCENE_VERSION}"
+            ),
+            SceneError::InvalidConstraint(index) => write!(
+                f,
+                "distance constraint {index} links an object that is not in the scene"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<std::io::Error> for SceneError {
+    fn from(error: std::io::Error) -> Self {
+        SceneError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for SceneError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SceneError::Parse(error)
+    }
+}
+
+impl From<ron::Error> for SceneError {
+    fn from(error: ron::Error) -> Self {
+        SceneError::Serialize(error)
+    }
+}
+
+impl Scene {
+    pub fn to_ron(&self) -> Result<String, SceneError> {
+        let config = ron::ser::PrettyConfig::new().struct_names(true);
+        Ok(ron::ser::to_string_pretty(self, config)?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Scene, SceneError> {
+        let scene: Scene = ron::from_str(text)?;
+        if scene.version > SCENE_VERSION {
+            return Err(SceneError::UnsupportedVersion(scene.version));
+        }
+        if let Some(index) = scene.invalid_constraint() {
+            return Err(SceneError::InvalidConstraint(index));
+        }
+        Ok(scene)
+    }
+
+    /// Index of the first distance constraint linking an object past the particles.
+    pub(crate) fn invalid_constraint(&self) -> Option<usize> {
+        let count = self.particles.len();
+        self.distance_constraints
+            .iter()
+            .position(|constraint| constraint.a >= count || constraint.b >= count)
+    }
+}
+
+impl Solver {
+    /// Snapshot of the solver settings together with `objects`.
+    pub fn to_scene(&self, objects: &[VerletObject]) -> Scene {
+        Scene {
+            version: SCENE_VERSION,
+            gravity: self.gravity(),
+            damping: self.damping(),
+            drag: self.drag(),
+            substeps: self.substeps(),
+            bounds: self.bounds(),
+            container: self.container(),
+            wall_material: self.wall_material(),
+            wall_restitution: self.wall_restitution(),
+            distance_constraints: self.distance_constraints().to_vec(),
+            particles: objects.to_vec(),
+        }
+    }
+
+    /// Take over the settings of `scene` and return its objects.
+    pub fn apply_scene(&mut self, scene: Scene) -> Vec<VerletObject> {
+        self.set_gravity(scene.gravity);
+        self.set_damping(scene.damping);
+        self.set_drag(scene.drag);
+        self.set_substeps(scene.substeps);
+        self.replace_shape(scene.bounds, scene.container);
+        self.set_wall_material(scene.wall_material);
+        self.set_wall_restitution(scene.wall_restitution);
+        self.clear_distance_constraints();
+        for constraint in scene.distance_constraints {
+            self.add_distance_constraint(constraint);
+        }
+        let mut particles = scene.particles;
+        for particle in &mut particles {
+            particle.position_step_start = particle.position_current;
+            // Hand-edited scenes may have a negative one
+            particle.set_inverse_mass(particle.inverse_mass);
+        }
+        particles
+    }
+
+    pub fn save_scene(
+        &self,
+        objects: &[VerletObject],
+        path: impl AsRef<Path>,
+    ) -> Result<(), SceneError> {
+        std::fs::write(path, self.to_scene(objects).to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn load_scene(&mut self, path: impl AsRef<Path>) -> Result<Vec<VerletObject>, SceneError> {
+        let scene = Scene::from_ron(&std::fs::read_to_string(path)?)?;
+        Ok(self.apply_scene(scene))
+    }
+}
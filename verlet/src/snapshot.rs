@@ -0,0 +1,360 @@
+use std::fmt;
+use std::path::Path;
+
+use glam::Vec2;
+
+use crate::{
+    Container, DistanceConstraint, Material, Scene, Solver, VerletObject, WallRestitution,
+    WorldBounds,
+};
+
+/// First bytes of every snapshot.
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"VRLT";
+/// Bumped whenever the binary layout changes.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+// magic, version, particle count, constraint count, step
+const HEADER_SIZE: usize = 4 + 4 + 8 + 8 + 8;
+// gravity, damping, drag, substeps, bounds, container tag, container center and
+// radius, wall material, restitution of the five walls
+const PARAMETERS_SIZE: usize = 8 + 4 + 4 + 4 + 16 + 4 + 12 + MATERIAL_SIZE + 5 * 4;
+// a, b, rest length, stiffness, max strain, max force
+const CONSTRAINT_SIZE: usize = 8 + 8 + 4 + 4 + 4 + 4;
+// position, previous position, radius, inverse mass, damping, material, collision
+// group, flags
+const PARTICLE_SIZE: usize = 8 + 8 + 4 + 4 + 4 + MATERIAL_SIZE + 4 + 4;
+// static and kinetic friction, restitution
+const MATERIAL_SIZE: usize = 4 + 4 + 4;
+
+const FLAG_PINNED: u32 = 1;
+
+/// Compact little-endian binary counterpart of [`Scene`], for states too large
+/// for a text format. Also records the step it was taken at.
+///
+/// The layout is a fixed-size header (magic, version, particle count,
+/// constraint count, step), the solver parameters, then fixed-size records for
+/// the distance constraints and the particles. It is encoded into and decoded
+/// from a single buffer.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub step: u64,
+    pub scene: Scene,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    BadMagic,
+    UnsupportedVersion(u32),
+    Truncated,
+    InvalidContainer(u32),
+    /// The distance constraint at this index links an object past the particles.
+    InvalidConstraint(usize),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "could not access snapshot file: {error}"),
+            SnapshotError::BadMagic => write!(f, "not a snapshot file"),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "snapshot version {version} is not supported (expected {SNAPSHOT_VERSION})"
+            ),
+            SnapshotError::Truncated => write!(f, "snapshot is truncated"),
+            SnapshotError::InvalidContainer(tag) => write!(f, "unknown container type {tag}"),
+            SnapshotError::InvalidConstraint(index) => write!(
+                f,
+                "distance constraint {index} links an object that is not in the snapshot"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(error: std::io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn vec2(&mut self, value: Vec2) {
+        self.f32(value.x);
+        self.f32(value.y);
+    }
+
+    /// NaN stands for `None`.
+    fn optional_f32(&mut self, value: Option<f32>) {
+        self.f32(value.unwrap_or(f32::NAN));
+    }
+
+    fn material(&mut self, value: Material) {
+        self.f32(value.static_friction);
+        self.f32(value.kinetic_friction);
+        self.optional_f32(value.restitution);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        if self.bytes.len() < N {
+            return Err(SnapshotError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(taken.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, SnapshotError> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    fn vec2(&mut self) -> Result<Vec2, SnapshotError> {
+        Ok(Vec2::new(self.f32()?, self.f32()?))
+    }
+
+    fn optional_f32(&mut self) -> Result<Option<f32>, SnapshotError> {
+        let value = self.f32()?;
+        Ok((!value.is_nan()).then_some(value))
+    }
+
+    fn material(&mut self) -> Result<Material, SnapshotError> {
+        Ok(Material {
+            static_friction: self.f32()?,
+            kinetic_friction: self.f32()?,
+            restitution: self.optional_f32()?,
+        })
+    }
+
+    /// Check up front that `count` records fit, so a corrupt count cannot
+    /// trigger a huge allocation.
+    fn expect_records(&self, count: u64, record_size: usize) -> Result<usize, SnapshotError> {
+        match usize::try_from(count) {
+            Ok(count) if count <= self.bytes.len() / record_size => Ok(count),
+            _ => Err(SnapshotError::Truncated),
+        }
+    }
+}
+
+impl Snapshot {
+    /// Snapshot of the solver and `objects` at the solver's current step.
+    pub fn capture(solver: &Solver, objects: &[VerletObject]) -> Snapshot {
+        Snapshot {
+            step: solver.step_count(),
+            scene: solver.to_scene(objects),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let scene = &self.scene;
+        let size = HEADER_SIZE
+            + PARAMETERS_SIZE
+            + scene.distance_constraints.len() * CONSTRAINT_SIZE
+            + scene.particles.len() * PARTICLE_SIZE;
+        let mut writer = Writer {
+            bytes: Vec::with_capacity(size),
+        };
+
+        writer.bytes.extend_from_slice(&SNAPSHOT_MAGIC);
+        writer.u32(SNAPSHOT_VERSION);
+        writer.u64(scene.particles.len() as u64);
+        writer.u64(scene.distance_constraints.len() as u64);
+        writer.u64(self.step);
+
+        writer.vec2(scene.gravity);
+        writer.f32(scene.damping);
+        writer.f32(scene.drag);
+        writer.u32(scene.substeps);
+        writer.vec2(scene.bounds.origin);
+        writer.vec2(scene.bounds.size);
+        let (tag, center, radius) = match scene.container {
+            Container::Box => (0, Vec2::ZERO, 0.0),
+            Container::Circle { center, radius } => (1, center, radius),
+            Container::InvertedCircle { center, radius } => (2, center, radius),
+        };
+        writer.u32(tag);
+        writer.vec2(center);
+        writer.f32(radius);
+        writer.material(scene.wall_material);
+        let restitution = &scene.wall_restitution;
+        for wall in [
+            restitution.left,
+            restitution.right,
+            restitution.top,
+            restitution.bottom,
+            restitution.circle,
+        ] {
+            writer.optional_f32(wall);
+        }
+
+        for constraint in &scene.distance_constraints {
+            writer.u64(constraint.a as u64);
+            writer.u64(constraint.b as u64);
+            writer.f32(constraint.rest_length);
+            writer.f32(constraint.stiffness);
+            writer.optional_f32(constraint.max_strain);
+            writer.optional_f32(constraint.max_force);
+        }
+
+        for particle in &scene.particles {
+            writer.vec2(particle.position_current);
+            writer.vec2(particle.position_old);
+            writer.f32(particle.radius);
+            writer.f32(particle.inverse_mass);
+            writer.f32(particle.damping);
+            writer.material(particle.material);
+            writer.u32(particle.collision_group);
+            writer.u32(if particle.pinned { FLAG_PINNED } else { 0 });
+        }
+
+        debug_assert_eq!(writer.bytes.len(), size);
+        writer.bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Snapshot, SnapshotError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take::<4>()? != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::BadMagic);
+        }
+        let version = reader.u32()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let particle_count = reader.u64()?;
+        let constraint_count = reader.u64()?;
+        let step = reader.u64()?;
+
+        let gravity = reader.vec2()?;
+        let damping = reader.f32()?;
+        let drag = reader.f32()?;
+        let substeps = reader.u32()?;
+        let bounds = WorldBounds::new(reader.vec2()?, reader.vec2()?);
+        let tag = reader.u32()?;
+        let center = reader.vec2()?;
+        let radius = reader.f32()?;
+        let container = match tag {
+            0 => Container::Box,
+            1 => Container::Circle { center, radius },
+            2 => Container::InvertedCircle { center, radius },
+            _ => return Err(SnapshotError::InvalidContainer(tag)),
+        };
+        let wall_material = reader.material()?;
+        let wall_restitution = WallRestitution {
+            left: reader.optional_f32()?,
+            right: reader.optional_f32()?,
+            top: reader.optional_f32()?,
+            bottom: reader.optional_f32()?,
+            circle: reader.optional_f32()?,
+        };
+
+        let constraint_count = reader.expect_records(constraint_count, CONSTRAINT_SIZE)?;
+        let mut distance_constraints = Vec::with_capacity(constraint_count);
+        for _ in 0..constraint_count {
+            let mut constraint = DistanceConstraint::new(
+                reader.u64()? as usize,
+                reader.u64()? as usize,
+                reader.f32()?,
+                reader.f32()?,
+            );
+            constraint.max_strain = reader.optional_f32()?;
+            constraint.max_force = reader.optional_f32()?;
+            distance_constraints.push(constraint);
+        }
+
+        let particle_count = reader.expect_records(particle_count, PARTICLE_SIZE)?;
+        let mut particles = Vec::with_capacity(particle_count);
+        for _ in 0..particle_count {
+            let mut particle = VerletObject::new(reader.vec2()?);
+            particle.position_old = reader.vec2()?;
+            particle.radius = reader.f32()?;
+            particle.set_inverse_mass(reader.f32()?);
+            particle.damping = reader.f32()?;
+            particle.material = reader.material()?;
+            particle.collision_group = reader.u32()?;
+            particle.pinned = reader.u32()? & FLAG_PINNED != 0;
+            particles.push(particle);
+        }
+
+        let scene = Scene {
+            version: crate::SCENE_VERSION,
+            gravity,
+            damping,
+            drag,
+            substeps,
+            bounds,
+            container,
+            wall_material,
+            wall_restitution,
+            distance_constraints,
+            particles,
+        };
+        if let Some(index) = scene.invalid_constraint() {
+            return Err(SnapshotError::InvalidConstraint(index));
+        }
+        Ok(Snapshot { step, scene })
+    }
+
+    pub fn write_to(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        std::fs::write(path, self.encode())?;
+        Ok(())
+    }
+
+    pub fn read_from(path: impl AsRef<Path>) -> Result<Snapshot, SnapshotError> {
+        Snapshot::decode(&std::fs::read(path)?)
+    }
+}
+
+impl Solver {
+    pub fn save_snapshot(
+        &self,
+        objects: &[VerletObject],
+        path: impl AsRef<Path>,
+    ) -> Result<(), SnapshotError> {
+        Snapshot::capture(self, objects).write_to(path)
+    }
+
+    /// Take over the settings and step count of `snapshot` and return its objects.
+    pub fn apply_snapshot(&mut self, snapshot: Snapshot) -> Vec<VerletObject> {
+        self.set_step_count(snapshot.step);
+        self.apply_scene(snapshot.scene)
+    }
+
+    pub fn load_snapshot(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<VerletObject>, SnapshotError> {
+        let snapshot = Snapshot::read_from(path)?;
+        Ok(self.apply_snapshot(snapshot))
+    }
+}
@@ -0,0 +1,125 @@
+use std::f32::consts::TAU;
+use std::ops::Range;
+
+use glam::Vec2;
+
+use crate::force::{signed_area, ForceGeneratorId, Pressure};
+use crate::object::unused_collision_group;
+use crate::{DistanceConstraint, Solver, VerletObject, DEFAULT_RADIUS};
+
+/// Builds a pressurised soft body: a closed loop of objects linked by distance
+/// constraints, with a [`Pressure`] generator keeping its area up, like a
+/// balloon or a blob.
+#[derive(Clone, Debug)]
+pub struct SoftBody {
+    points: Vec<Vec2>,
+    stiffness: f32,
+    pressure: f32,
+    radius: f32,
+}
+
+impl SoftBody {
+    /// Round body of `points` objects on a circle of `radius` around `center`.
+    pub fn circle(center: Vec2, radius: f32, points: usize) -> Self {
+        let points = points.max(3);
+        SoftBody::along(
+            (0..points)
+                .map(|i| center + Vec2::from_angle(TAU * i as f32 / points as f32) * radius)
+                .collect(),
+        )
+    }
+
+    /// Body with an object at every point of the closed polygon `points`.
+    pub fn along(points: Vec<Vec2>) -> Self {
+        SoftBody {
+            points,
+            stiffness: 1.0,
+            pressure: 2000.0,
+            radius: DEFAULT_RADIUS,
+        }
+    }
+
+    /// Stiffness of the links around the loop, see [`DistanceConstraint`].
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// See [`Pressure::pressure`]. Higher values make a firmer body, 0 an
+    /// empty loop that collapses like a rope.
+    pub fn with_pressure(mut self, pressure: f32) -> Self {
+        self.pressure = pressure;
+        self
+    }
+
+    /// Radius of the objects around the loop.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Append the body's objects to `objects`, and its links and pressure to
+    /// the solver. The pressure aims for the area the body is built with. The
+    /// objects share a collision group, so neighbours do not push each other
+    /// apart; they collide with everything else.
+    pub fn build(&self, solver: &mut Solver, objects: &mut Vec<VerletObject>) -> SoftBodyHandle {
+        let first = objects.len();
+        let group = unused_collision_group(objects);
+        objects.extend(self.points.iter().map(|&point| {
+            VerletObject::new(point)
+                .with_radius(self.radius)
+                .with_collision_group(group)
+        }));
+        let indices = first..objects.len();
+        for a in indices.clone() {
+            let b = if a + 1 == indices.end {
+                indices.start
+            } else {
+                a + 1
+            };
+            if a != b {
+                solver.add_distance_constraint(DistanceConstraint::between(
+                    objects,
+                    a,
+                    b,
+                    self.stiffness,
+                ));
+            }
+        }
+        let mut pressure = Pressure::new(indices.clone().collect(), 0.0, self.pressure);
+        pressure.target_area = pressure.area(objects);
+        SoftBodyHandle {
+            indices,
+            pressure: solver.add_force_generator(pressure),
+        }
+    }
+}
+
+/// Soft body made by [`SoftBody::build`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoftBodyHandle {
+    indices: Range<usize>,
+    pressure: ForceGeneratorId,
+}
+
+impl SoftBodyHandle {
+    /// Indices of the objects around the loop, in order.
+    pub fn indices(&self) -> Range<usize> {
+        self.indices.clone()
+    }
+
+    /// The body's pressure generator, to remove it from the solver.
+    pub fn pressure(&self) -> ForceGeneratorId {
+        self.pressure
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.contains(&index)
+    }
+
+    /// Area currently enclosed by the loop.
+    pub fn area(&self, objects: &[VerletObject]) -> f32 {
+        let indices: Vec<usize> = self.indices().collect();
+        signed_area(&indices, objects).abs()
+    }
+}
@@ -0,0 +1,599 @@
+use std::ops::AddAssign;
+use std::sync::Arc;
+
+use glam::Vec2;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+use crate::collision::{self, Broadphase, CollisionGrid};
+use crate::constraint::ConstraintBroken;
+use crate::force::{ForceGenerator, ForceGeneratorId};
+use crate::grab::Grab;
+use crate::{
+    Container, DistanceConstraint, FixedTimestep, Material, VerletObject, WallRestitution,
+    WorldBounds,
+};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DebugTimeInfo {
+    pub gravity_time: f32,
+    pub forces_time: f32,
+    pub constraints_time: f32,
+    pub collisions_time: f32,
+    pub distance_constraints_time: f32,
+    pub update_positions_time: f32,
+}
+
+impl AddAssign for DebugTimeInfo {
+    fn add_assign(&mut self, other: Self) {
+        self.gravity_time += other.gravity_time;
+        self.forces_time += other.forces_time;
+        self.constraints_time += other.constraints_time;
+        self.collisions_time += other.collisions_time;
+        self.distance_constraints_time += other.distance_constraints_time;
+        self.update_positions_time += other.update_positions_time;
+    }
+}
+
+/// Result of [`Solver::advance`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StepReport {
+    /// Fixed steps run this frame.
+    pub steps: u32,
+    /// Fraction of a step left over, to blend with [`VerletObject::interpolated_position`].
+    pub alpha: f32,
+    /// Summed over all steps.
+    pub timings: DebugTimeInfo,
+}
+
+#[derive(Debug)]
+pub struct Solver {
+    gravity: Vec2,
+    damping: f32,
+    drag: f32,
+    substeps: u32,
+    fixed_timestep: FixedTimestep,
+    bounds: WorldBounds,
+    container: Container,
+    // The bounds or container changed since the last update
+    reshaped: bool,
+    wall_material: Material,
+    wall_restitution: WallRestitution,
+    broadphase: Box<dyn Broadphase>,
+    distance_constraints: Vec<DistanceConstraint>,
+    // Not drained yet
+    broken_constraints: Vec<ConstraintBroken>,
+    force_generators: Vec<(ForceGeneratorId, Box<dyn ForceGenerator>)>,
+    next_force_generator: u64,
+    grab: Option<Grab>,
+    // None runs on rayon's global pool
+    pool: Option<Arc<ThreadPool>>,
+    deterministic: bool,
+    step: u64,
+    // Simulation time handed to the force generators
+    time: f64,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver {
+            gravity: Vec2::new(0.0, 1000.0),
+            damping: 0.0,
+            drag: 0.0,
+            substeps: 8,
+            fixed_timestep: FixedTimestep::default(),
+            bounds: WorldBounds::default(),
+            container: Container::Box,
+            reshaped: false,
+            wall_material: Material::FRICTIONLESS,
+            wall_restitution: WallRestitution::default(),
+            broadphase: Box::new(CollisionGrid::new()),
+            distance_constraints: Vec::new(),
+            broken_constraints: Vec::new(),
+            force_generators: Vec::new(),
+            next_force_generator: 0,
+            grab: None,
+            pool: None,
+            deterministic: false,
+            step: 0,
+            time: 0.0,
+        }
+    }
+
+    pub fn gravity(&self) -> Vec2 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vec2) {
+        self.gravity = gravity;
+    }
+
+    /// Linear damping rate per second applied to every object, see
+    /// [`Solver::set_damping`].
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Velocities decay by `exp(-damping * t)` over `t` seconds, whatever the
+    /// substep count. Objects can add their own with [`VerletObject::set_damping`].
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.max(0.0);
+    }
+
+    /// Quadratic air drag coefficient, see [`Solver::set_drag`].
+    pub fn drag(&self) -> f32 {
+        self.drag
+    }
+
+    /// Every object feels a force of `drag * speed²` against its motion, so
+    /// falling objects reach a terminal speed of `sqrt(g * mass / drag)`.
+    /// Heavier objects are slowed down less. 0 turns it off.
+    pub fn set_drag(&mut self, drag: f32) {
+        self.drag = drag.max(0.0);
+    }
+
+    /// Substeps per fixed step in [`Solver::advance`].
+    pub fn substeps(&self) -> u32 {
+        self.substeps
+    }
+
+    pub fn set_substeps(&mut self, substeps: u32) {
+        self.substeps = substeps.max(1);
+    }
+
+    pub fn fixed_timestep(&self) -> FixedTimestep {
+        self.fixed_timestep
+    }
+
+    /// # Panics
+    /// If its `dt` was changed to something not greater than 0.
+    pub fn set_fixed_timestep(&mut self, fixed_timestep: FixedTimestep) {
+        let dt = fixed_timestep.dt;
+        assert!(dt > 0.0, "fixed timestep must be greater than 0, got {dt}");
+        self.fixed_timestep = fixed_timestep;
+    }
+
+    pub fn bounds(&self) -> WorldBounds {
+        self.bounds
+    }
+
+    /// Can be changed between updates. Particles left outside after a shrink
+    /// are moved back in on the next substep without gaining any velocity.
+    pub fn set_bounds(&mut self, bounds: WorldBounds) {
+        self.reshaped |= bounds != self.bounds;
+        self.bounds = bounds;
+    }
+
+    pub fn broadphase(&self) -> &dyn Broadphase {
+        self.broadphase.as_ref()
+    }
+
+    /// Swap the broadphase used to find colliding pairs. The default
+    /// [`CollisionGrid`] needs the objects to stay near the world bounds, a
+    /// [`collision::SpatialHash`] copes with unbounded worlds.
+    pub fn set_broadphase<B: Broadphase + 'static>(&mut self, broadphase: B) {
+        self.broadphase = Box::new(broadphase);
+    }
+
+    pub fn container(&self) -> Container {
+        self.container
+    }
+
+    /// Like the bounds, can be swapped between updates without launching objects.
+    pub fn set_container(&mut self, container: Container) {
+        self.reshaped |= container != self.container;
+        self.container = container;
+    }
+
+    /// Take over `bounds` and `container` along with objects that already fit
+    /// them, e.g. from a scene, so none are treated as caught outside.
+    pub(crate) fn replace_shape(&mut self, bounds: WorldBounds, container: Container) {
+        self.bounds = bounds;
+        self.container = container;
+        self.reshaped = false;
+    }
+
+    pub fn wall_material(&self) -> Material {
+        self.wall_material
+    }
+
+    /// Surface of the container walls, combined with each object's material
+    /// for the friction along them.
+    pub fn set_wall_material(&mut self, material: Material) {
+        self.wall_material = material;
+    }
+
+    pub fn wall_restitution(&self) -> WallRestitution {
+        self.wall_restitution
+    }
+
+    /// How much of the velocity into each wall is kept, reflected, when an
+    /// object hits it.
+    pub fn set_wall_restitution(&mut self, restitution: WallRestitution) {
+        self.wall_restitution = restitution;
+    }
+
+    pub fn distance_constraints(&self) -> &[DistanceConstraint] {
+        &self.distance_constraints
+    }
+
+    /// Returns the index of the new constraint in
+    /// [`Solver::distance_constraints`]. It only stays valid until the
+    /// constraints change: an update can break and remove constraints, and
+    /// removing one moves another into its place.
+    pub fn add_distance_constraint(&mut self, constraint: DistanceConstraint) -> usize {
+        self.distance_constraints.push(constraint);
+        self.distance_constraints.len() - 1
+    }
+
+    /// Removes the constraint at `index`, moving the last one into its place.
+    pub fn remove_distance_constraint(&mut self, index: usize) -> DistanceConstraint {
+        self.distance_constraints.swap_remove(index)
+    }
+
+    /// Keep only the constraints `keep` returns true for, in the same order.
+    pub fn retain_distance_constraints(&mut self, keep: impl FnMut(&DistanceConstraint) -> bool) {
+        self.distance_constraints.retain(keep);
+    }
+
+    /// Break the constraints `breaks` returns true for, e.g. cut with a tool:
+    /// like [`Solver::retain_distance_constraints`], but each one is reported
+    /// as a [`ConstraintBroken`] at the current step. Returns how many broke.
+    pub fn break_distance_constraints(
+        &mut self,
+        mut breaks: impl FnMut(&DistanceConstraint) -> bool,
+    ) -> usize {
+        let count = self.broken_constraints.len();
+        let step = self.step;
+        let broken = &mut self.broken_constraints;
+        self.distance_constraints.retain(|constraint| {
+            let breaks = breaks(constraint);
+            if breaks {
+                broken.push(ConstraintBroken {
+                    a: constraint.a,
+                    b: constraint.b,
+                    step,
+                });
+            }
+            !breaks
+        });
+        self.broken_constraints.len() - count
+    }
+
+    pub fn clear_distance_constraints(&mut self) {
+        self.distance_constraints.clear();
+    }
+
+    /// Constraints that broke since the last call, oldest first. They have
+    /// already been removed. Events pile up until drained.
+    pub fn drain_broken_constraints(&mut self) -> std::vec::Drain<'_, ConstraintBroken> {
+        self.broken_constraints.drain(..)
+    }
+
+    /// Register a force field, evaluated every substep until it is removed or
+    /// reports itself finished.
+    pub fn add_force_generator<G: ForceGenerator + 'static>(
+        &mut self,
+        generator: G,
+    ) -> ForceGeneratorId {
+        let id = ForceGeneratorId(self.next_force_generator);
+        self.next_force_generator += 1;
+        self.force_generators.push((id, Box::new(generator)));
+        id
+    }
+
+    /// Returns false if there is no such generator, e.g. it already finished.
+    pub fn remove_force_generator(&mut self, id: ForceGeneratorId) -> bool {
+        let count = self.force_generators.len();
+        self.force_generators.retain(|(other, _)| *other != id);
+        self.force_generators.len() != count
+    }
+
+    pub fn clear_force_generators(&mut self) {
+        self.force_generators.clear();
+    }
+
+    pub fn force_generator_count(&self) -> usize {
+        self.force_generators.len()
+    }
+
+    /// Seconds simulated so far, as seen by the force generators.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Start dragging the object at `index`, see [`Solver::set_grab_target`].
+    pub fn grab(&mut self, objects: &[VerletObject], index: usize) {
+        self.grab = objects
+            .get(index)
+            .map(|object| Grab::new(index, object.get_position()));
+    }
+
+    /// Where the grabbed object should be at the end of the next update.
+    pub fn set_grab_target(&mut self, target: Vec2) {
+        if let Some(grab) = &mut self.grab {
+            grab.target = target;
+        }
+    }
+
+    /// Let go of the grabbed object, which keeps the velocity it was dragged with.
+    pub fn release(&mut self) {
+        self.grab = None;
+    }
+
+    /// Index of the grabbed object.
+    pub fn grabbed(&self) -> Option<usize> {
+        self.grab.map(|grab| grab.index)
+    }
+
+    /// Number of threads the parallel phases run on.
+    pub fn thread_count(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    /// Run the solver on its own pool of `threads` threads. 0 picks rayon's
+    /// default (one per CPU), 1 solves collisions on the serial path.
+    pub fn set_thread_count(&mut self, threads: usize) -> Result<(), ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+        self.pool = Some(Arc::new(pool));
+        Ok(())
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// In deterministic mode the same objects, dt and substeps always give
+    /// bit-for-bit the same result, whatever the thread count. Collisions are
+    /// then solved in a fixed order on one thread, which is slower.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    /// Number of `update` calls so far.
+    pub fn step_count(&self) -> u64 {
+        self.step
+    }
+
+    pub fn set_step_count(&mut self, step: u64) {
+        self.step = step;
+    }
+
+    pub fn update(
+        &mut self,
+        objects: &mut [VerletObject],
+        dt: f32,
+        substeps: u32,
+    ) -> DebugTimeInfo {
+        for object in objects.iter_mut() {
+            object.position_step_start = object.position_current;
+        }
+        let timings = match self.pool.clone() {
+            Some(pool) => pool.install(|| self.run_substeps(objects, dt, substeps)),
+            None => self.run_substeps(objects, dt, substeps),
+        };
+        self.step += 1;
+        timings
+    }
+
+    /// Run steps of `dt` with the solver's substeps until `step_count` reaches
+    /// `step`, e.g. to take a snapshot at that step from a headless run.
+    pub fn run_to_step(
+        &mut self,
+        objects: &mut [VerletObject],
+        dt: f32,
+        step: u64,
+    ) -> DebugTimeInfo {
+        let mut timings = DebugTimeInfo::default();
+        while self.step < step {
+            timings += self.update(objects, dt, self.substeps);
+        }
+        timings
+    }
+
+    /// Spend `frame_time` seconds of real time in whole fixed steps.
+    ///
+    /// Leftover time is carried over to the next call. Rendering can use the
+    /// returned alpha to blend between the positions before and after the
+    /// last step.
+    pub fn advance(&mut self, objects: &mut [VerletObject], frame_time: f32) -> StepReport {
+        let mut report = StepReport::default();
+        self.fixed_timestep.accumulate(frame_time);
+        while report.steps < self.fixed_timestep.max_steps_per_frame
+            && self.fixed_timestep.consume_step()
+        {
+            report.timings += self.update(objects, self.fixed_timestep.dt, self.substeps);
+            report.steps += 1;
+        }
+        // Still behind after the maximum number of steps, let the simulation slow
+        // down instead of spiralling
+        self.fixed_timestep.drop_backlog();
+        report.alpha = self.fixed_timestep.alpha();
+        report
+    }
+
+    fn run_substeps(
+        &mut self,
+        objects: &mut [VerletObject],
+        dt: f32,
+        substeps: u32,
+    ) -> DebugTimeInfo {
+        let sub_dt = dt / substeps as f32;
+        let mut gravity_time = 0.0;
+        let mut forces_time = 0.0;
+        let mut constraints_time = 0.0;
+        let mut collisions_time = 0.0;
+        let mut distance_constraints_time = 0.0;
+        let mut update_positions_time = 0.0;
+        let reshaped = std::mem::take(&mut self.reshaped);
+        for substep in 0..substeps {
+            gravity_time += Self::apply_gravity(objects, &self.gravity);
+            forces_time +=
+                Self::apply_forces(objects, &mut self.force_generators, self.time, sub_dt);
+            constraints_time += Self::apply_constraints(
+                objects,
+                &self.bounds,
+                &self.container,
+                self.wall_material,
+                &self.wall_restitution,
+                reshaped && substep == 0,
+            );
+            collisions_time += Self::solve_collisions(
+                self.broadphase.as_mut(),
+                objects,
+                &self.bounds,
+                self.deterministic,
+            );
+            distance_constraints_time += Self::apply_distance_constraints(
+                objects,
+                &mut self.distance_constraints,
+                &mut self.broken_constraints,
+                sub_dt,
+                self.step + 1,
+            );
+            if let Some(grab) = &self.grab {
+                grab.apply(objects, (substep + 1) as f32 / substeps as f32);
+            }
+            update_positions_time +=
+                Self::update_positions(objects, sub_dt, self.damping, self.drag);
+            self.time += sub_dt as f64;
+        }
+        if let Some(grab) = &mut self.grab {
+            grab.finish_update();
+        }
+        DebugTimeInfo {
+            gravity_time,
+            forces_time,
+            constraints_time,
+            collisions_time,
+            distance_constraints_time,
+            update_positions_time,
+        }
+    }
+
+    fn apply_gravity(objects: &mut [VerletObject], gravity: &Vec2) -> f32 {
+        let now = std::time::Instant::now();
+        for object in objects.iter_mut() {
+            object.accelerate(*gravity);
+        }
+        now.elapsed().as_secs_f32()
+    }
+
+    fn apply_forces(
+        objects: &mut [VerletObject],
+        generators: &mut Vec<(ForceGeneratorId, Box<dyn ForceGenerator>)>,
+        time: f64,
+        dt: f32,
+    ) -> f32 {
+        let now = std::time::Instant::now();
+        for (_, generator) in generators.iter_mut() {
+            generator.apply(objects, time, dt);
+        }
+        generators.retain(|(_, generator)| !generator.is_finished());
+        now.elapsed().as_secs_f32()
+    }
+
+    fn update_positions(objects: &mut [VerletObject], dt: f32, damping: f32, drag: f32) -> f32 {
+        let now = std::time::Instant::now();
+        objects.par_iter_mut().for_each(|object| {
+            object.update_position_damped(dt, damping, drag);
+        });
+        now.elapsed().as_secs_f32()
+    }
+
+    fn apply_constraints(
+        objects: &mut [VerletObject],
+        bounds: &WorldBounds,
+        container: &Container,
+        wall_material: Material,
+        restitution: &WallRestitution,
+        reshaped: bool,
+    ) -> f32 {
+        let now = std::time::Instant::now();
+        for object in objects.iter_mut().filter(|object| !object.is_static()) {
+            let position = object.get_position();
+            let mut velocity = object.get_velocity();
+            let mut bounced = false;
+            let constrained =
+                container.constrain_with_walls(bounds, position, object.radius, |wall, n| {
+                    if let Some(restitution) = restitution.get(wall) {
+                        let into_wall = velocity.dot(n);
+                        if into_wall < 0.0 {
+                            velocity -= (1.0 + restitution) * into_wall * n;
+                            bounced = true;
+                        }
+                    }
+                });
+            let correction = constrained - position;
+            if reshaped && correction != Vec2::ZERO {
+                // Caught outside by a shrink: move the whole object back in
+                // instead of clamping, which would launch it with the correction.
+                object.position_old += correction;
+                object.position_current = constrained;
+                continue;
+            }
+            object.position_current = constrained;
+            if bounced {
+                // Reflect the velocity instead of leaving what the clamp gives
+                object.position_old = constrained - velocity;
+            }
+
+            let material = object.material.combine(wall_material);
+            let depth = correction.length();
+            if depth > 0.0 && material.has_friction() {
+                // Against the wall, which does not move
+                let n = correction / depth;
+                let velocity = object.get_velocity();
+                let slip = velocity - velocity.dot(n) * n;
+                object.position_current -= material.friction(slip, depth);
+            }
+        }
+        now.elapsed().as_secs_f32()
+    }
+
+    fn apply_distance_constraints(
+        objects: &mut [VerletObject],
+        constraints: &mut Vec<DistanceConstraint>,
+        broken: &mut Vec<ConstraintBroken>,
+        dt: f32,
+        step: u64,
+    ) -> f32 {
+        let now = std::time::Instant::now();
+        constraints.retain(|constraint| {
+            let holds = constraint.relax(objects, dt);
+            if !holds {
+                broken.push(ConstraintBroken {
+                    a: constraint.a,
+                    b: constraint.b,
+                    step,
+                });
+            }
+            holds
+        });
+        now.elapsed().as_secs_f32()
+    }
+
+    fn solve_collisions<B: Broadphase + ?Sized>(
+        broadphase: &mut B,
+        objects: &mut [VerletObject],
+        bounds: &WorldBounds,
+        deterministic: bool,
+    ) -> f32 {
+        // returns time in seconds
+        let now = std::time::Instant::now();
+        // The parallel strips depend on the thread count, and so does the order
+        // the contacts are resolved in
+        let parallel = rayon::current_num_threads() > 1 && !deterministic;
+        collision::solve_collisions(broadphase, objects, bounds, parallel);
+        now.elapsed().as_secs_f32()
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
@@ -0,0 +1,54 @@
+/// Settings and accumulated time for `Solver::advance`.
+///
+/// Real frame time is collected and spent in whole steps of `dt`, so the
+/// simulation no longer depends on the frame rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedTimestep {
+    pub dt: f32,
+    /// Steps run in a single `advance` at most. Time beyond that is dropped so a
+    /// slow frame cannot snowball into ever longer frames.
+    pub max_steps_per_frame: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// # Panics
+    /// If `dt` is not greater than 0.
+    pub fn new(dt: f32, max_steps_per_frame: u32) -> Self {
+        assert!(dt > 0.0, "fixed timestep must be greater than 0, got {dt}");
+        FixedTimestep {
+            dt,
+            max_steps_per_frame,
+            accumulator: 0.0,
+        }
+    }
+
+    /// How far the leftover time is into the next step, in `[0, 1)`.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.dt
+    }
+
+    pub(crate) fn accumulate(&mut self, frame_time: f32) {
+        self.accumulator += frame_time.max(0.0);
+    }
+
+    /// Take one step's worth of time if there is enough left.
+    pub(crate) fn consume_step(&mut self) -> bool {
+        if self.accumulator >= self.dt {
+            self.accumulator -= self.dt;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn drop_backlog(&mut self) {
+        self.accumulator %= self.dt;
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        FixedTimestep::new(1.0 / 60.0, 4)
+    }
+}
@@ -0,0 +1,98 @@
+use verlet::{Cloth, ClothMesh, Solver, Vec2, VerletObject};
+
+fn hanging_cloth(tear_stretch: Option<f32>) -> (Solver, Vec<VerletObject>, ClothMesh) {
+    let mut solver = Solver::new();
+    let mut objects = Vec::new();
+    let mut cloth = Cloth::new(Vec2::new(300.0, 50.0), 11, 8, 10.0);
+    if let Some(stretch) = tear_stretch {
+        cloth = cloth.with_tear_stretch(stretch);
+    }
+    let mesh = cloth.build(&mut solver, &mut objects);
+    (solver, objects, mesh)
+}
+
+#[test]
+fn build_links_the_grid() {
+    let mut solver = Solver::new();
+    let mut objects = vec![VerletObject::new(Vec2::new(10.0, 10.0))];
+    let mesh = Cloth::new(Vec2::new(100.0, 100.0), 4, 3, 10.0).build(&mut solver, &mut objects);
+
+    assert_eq!(mesh.indices(), 1..13);
+    assert_eq!(mesh.index(3, 2), 12);
+    assert_eq!(
+        objects[mesh.index(2, 1)].get_position(),
+        Vec2::new(120.0, 110.0)
+    );
+    // 17 structural, 12 shear and 10 bend links
+    assert_eq!(solver.distance_constraints().len(), 39);
+    for column in 0..4 {
+        assert!(objects[mesh.index(column, 0)].is_pinned());
+        assert!(!objects[mesh.index(column, 1)].is_pinned());
+    }
+    let triangles = mesh.triangles(&solver, &objects);
+    assert_eq!(triangles.len(), 12);
+    assert!(triangles
+        .iter()
+        .all(|triangle| (triangle.stretch - 1.0).abs() < 1e-6));
+}
+
+#[test]
+fn cut_separates_the_bottom() {
+    let (mut solver, mut objects, mesh) = hanging_cloth(None);
+    for _ in 0..30 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    let triangles = mesh.triangles(&solver, &objects).len();
+
+    // Across the whole cloth between the fourth and fifth rows
+    let y = (objects[mesh.index(5, 3)].get_position().y
+        + objects[mesh.index(5, 4)].get_position().y)
+        / 2.0;
+    let cut = mesh.cut(
+        &mut solver,
+        &objects,
+        Vec2::new(0.0, y),
+        Vec2::new(800.0, y),
+    );
+    assert!(cut > 0);
+    assert!(mesh.triangles(&solver, &objects).len() < triangles);
+    // Counted like torn links
+    let broken: Vec<_> = solver.drain_broken_constraints().collect();
+    assert_eq!(broken.len(), cut);
+    assert!(broken
+        .iter()
+        .all(|link| link.step == 30 && mesh.contains(link.a) && mesh.contains(link.b)));
+
+    for _ in 0..30 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    // The bottom half fell away, the top half still hangs
+    let top = objects[mesh.index(5, 3)].get_position().y;
+    let bottom = objects[mesh.index(5, 4)].get_position().y;
+    assert!(top < 150.0);
+    assert!(bottom - top > 100.0);
+}
+
+#[test]
+fn tears_only_past_the_tear_stretch() {
+    let (mut solver, mut objects, mesh) = hanging_cloth(Some(1.5));
+    let links = solver.distance_constraints().len();
+    for _ in 0..30 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert_eq!(solver.distance_constraints().len(), links);
+    assert_eq!(solver.drain_broken_constraints().count(), 0);
+
+    // Yank a bottom corner away
+    let corner = mesh.index(0, 7);
+    solver.grab(&objects, corner);
+    solver.set_grab_target(Vec2::new(100.0, 500.0));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    let broken = solver.drain_broken_constraints().count();
+    assert!(broken > 0);
+    assert_eq!(solver.distance_constraints().len(), links - broken);
+    assert!(solver
+        .distance_constraints()
+        .iter()
+        .all(|constraint| constraint.stretch(&objects) <= 1.5));
+}
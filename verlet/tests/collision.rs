@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+
+use verlet::collision::{solve_collisions_brute_force, CollisionGrid, SpatialHash};
+use verlet::{Vec2, VerletObject, WorldBounds, DEFAULT_RADIUS};
+
+/// Small deterministic LCG so the scenes are reproducible without extra dependencies.
+fn random_objects(count: usize, bounds: &WorldBounds, seed: u64) -> Vec<VerletObject> {
+    let mut state = seed;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 40) as f32 / (1u64 << 24) as f32
+    };
+    (0..count)
+        .map(|_| VerletObject::new(bounds.origin + Vec2::new(next(), next()) * bounds.size))
+        .collect()
+}
+
+fn touching(objects: &[VerletObject], i: usize, j: usize) -> bool {
+    objects[i]
+        .get_position()
+        .distance(objects[j].get_position())
+        < objects[i].get_radius() + objects[j].get_radius()
+}
+
+fn brute_force_contacts(objects: &[VerletObject]) -> HashSet<(usize, usize)> {
+    let mut contacts = HashSet::new();
+    for i in 0..objects.len() {
+        for j in i + 1..objects.len() {
+            if touching(objects, i, j) {
+                contacts.insert((i, j));
+            }
+        }
+    }
+    contacts
+}
+
+fn grid_contacts(objects: &[VerletObject], bounds: &WorldBounds) -> HashSet<(usize, usize)> {
+    let mut grid = CollisionGrid::new();
+    grid.rebuild_with_cell_size(objects, bounds, CollisionGrid::cell_size_for(objects));
+    let mut contacts = HashSet::new();
+    grid.for_each_pair(|i, j| {
+        if touching(objects, i, j) {
+            // Every pair must be reported exactly once
+            assert!(contacts.insert((i.min(j), i.max(j))));
+        }
+    });
+    contacts
+}
+
+fn spatial_hash_contacts(objects: &[VerletObject]) -> HashSet<(usize, usize)> {
+    let mut hash = SpatialHash::new();
+    hash.rebuild_with_cell_size(objects, CollisionGrid::cell_size_for(objects));
+    let mut contacts = HashSet::new();
+    hash.for_each_pair(|i, j| {
+        if touching(objects, i, j) {
+            assert!(contacts.insert((i.min(j), i.max(j))));
+        }
+    });
+    contacts
+}
+
+#[test]
+fn grid_finds_same_contacts_as_brute_force() {
+    let bounds = WorldBounds::new(Vec2::new(-50.0, 20.0), Vec2::new(300.0, 200.0));
+    let objects = random_objects(2000, &bounds, 1);
+
+    let expected = brute_force_contacts(&objects);
+    assert!(!expected.is_empty());
+    assert_eq!(grid_contacts(&objects, &bounds), expected);
+}
+
+#[test]
+fn grid_finds_contacts_between_mixed_sizes() {
+    let bounds = WorldBounds::from_size(Vec2::new(300.0, 300.0));
+    let mut objects = random_objects(1500, &bounds, 3);
+    for (i, object) in objects.iter_mut().enumerate() {
+        object.set_radius([1.0, 2.5, 4.0, 9.0][i % 4]);
+    }
+
+    let expected = brute_force_contacts(&objects);
+    assert!(!expected.is_empty());
+    assert_eq!(grid_contacts(&objects, &bounds), expected);
+}
+
+#[test]
+fn grid_stays_small_for_a_few_objects_in_a_large_world() {
+    let bounds = WorldBounds::from_size(Vec2::new(100_000.0, 100_000.0));
+    let objects = vec![
+        VerletObject::new(Vec2::new(500.0, 500.0)).with_radius(0.05),
+        VerletObject::new(Vec2::new(500.05, 500.0)).with_radius(0.05),
+    ];
+    let mut grid = CollisionGrid::new();
+    grid.rebuild_with_cell_size(&objects, &bounds, CollisionGrid::cell_size_for(&objects));
+    assert!(grid.cols() * grid.rows() <= 1024);
+    assert_eq!(grid_contacts(&objects, &bounds), HashSet::from([(0, 1)]));
+}
+
+#[test]
+fn grid_handles_objects_outside_bounds() {
+    let bounds = WorldBounds::from_size(Vec2::new(60.0, 60.0));
+    let objects = vec![
+        VerletObject::new(Vec2::new(-100.0, -100.0)),
+        VerletObject::new(Vec2::new(-100.0 + DEFAULT_RADIUS, -100.0)),
+        VerletObject::new(Vec2::new(500.0, 30.0)),
+        VerletObject::new(Vec2::new(500.0, 30.0 + DEFAULT_RADIUS)),
+    ];
+    assert_eq!(
+        grid_contacts(&objects, &bounds),
+        HashSet::from([(0, 1), (2, 3)])
+    );
+}
+
+#[test]
+fn grid_matches_brute_force_within_tolerance() {
+    let bounds = WorldBounds::from_size(Vec2::new(400.0, 400.0));
+    let mut brute_force = random_objects(600, &bounds, 7);
+    let mut grid_objects = brute_force.clone();
+
+    let mut grid = CollisionGrid::new();
+    for _ in 0..8 {
+        solve_collisions_brute_force(&mut brute_force);
+        grid.rebuild_with_cell_size(&grid_objects, &bounds, 2.0 * DEFAULT_RADIUS);
+        grid.solve_collisions(&mut grid_objects);
+    }
+
+    // Pairs are relaxed in a different order, so only the aggregate has to agree
+    let mean_error = brute_force
+        .iter()
+        .zip(&grid_objects)
+        .map(|(a, b)| a.get_position().distance(b.get_position()))
+        .sum::<f32>()
+        / brute_force.len() as f32;
+    assert!(mean_error < 0.1 * DEFAULT_RADIUS, "mean error {mean_error}");
+}
+
+#[test]
+fn parallel_grid_matches_serial_grid_within_tolerance() {
+    let bounds = WorldBounds::from_size(Vec2::new(400.0, 400.0));
+    let mut serial = random_objects(600, &bounds, 11);
+    let mut parallel = serial.clone();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let mut grid = CollisionGrid::new();
+    for _ in 0..8 {
+        grid.rebuild_with_cell_size(&serial, &bounds, 2.0 * DEFAULT_RADIUS);
+        grid.solve_collisions(&mut serial);
+        grid.rebuild_with_cell_size(&parallel, &bounds, 2.0 * DEFAULT_RADIUS);
+        pool.install(|| grid.solve_collisions_parallel(&mut parallel));
+    }
+
+    let mean_error = serial
+        .iter()
+        .zip(&parallel)
+        .map(|(a, b)| a.get_position().distance(b.get_position()))
+        .sum::<f32>()
+        / serial.len() as f32;
+    assert!(mean_error < 0.1 * DEFAULT_RADIUS, "mean error {mean_error}");
+}
+
+#[test]
+fn correction_is_split_by_inverse_mass() {
+    let mut objects = vec![
+        VerletObject::new(Vec2::new(0.0, 0.0)).with_mass(3.0),
+        VerletObject::new(Vec2::new(DEFAULT_RADIUS, 0.0)),
+        VerletObject::new(Vec2::new(50.0, 0.0)).with_inverse_mass(0.0),
+        VerletObject::new(Vec2::new(50.0 + DEFAULT_RADIUS, 0.0)),
+    ];
+    solve_collisions_brute_force(&mut objects);
+
+    // Overlap of one radius, the light object takes three quarters of it
+    let overlap = DEFAULT_RADIUS;
+    assert!((objects[0].get_position().x + 0.25 * overlap).abs() < 1e-5);
+    assert!((objects[1].get_position().x - 1.75 * overlap).abs() < 1e-5);
+    // A static object never moves, the other one takes the whole correction
+    assert_eq!(objects[2].get_position(), Vec2::new(50.0, 0.0));
+    assert!((objects[3].get_position().x - (50.0 + 2.0 * overlap)).abs() < 1e-5);
+}
+
+#[test]
+#[should_panic(expected = "mass must be greater than 0")]
+fn zero_mass_is_rejected() {
+    VerletObject::new(Vec2::ZERO).with_mass(0.0);
+}
+
+#[test]
+fn negative_inverse_mass_is_static() {
+    let mut objects = vec![
+        VerletObject::new(Vec2::ZERO).with_inverse_mass(-1.0),
+        VerletObject::new(Vec2::new(DEFAULT_RADIUS, 0.0)),
+    ];
+    assert_eq!(objects[0].get_inverse_mass(), 0.0);
+    assert!(objects[0].is_static());
+    solve_collisions_brute_force(&mut objects);
+    assert_eq!(objects[0].get_position(), Vec2::ZERO);
+    assert!(objects[1].get_position().is_finite());
+}
+
+#[test]
+fn spatial_hash_finds_same_contacts_as_brute_force() {
+    // Spread over negative coordinates and with mixed sizes
+    let bounds = WorldBounds::new(Vec2::new(-200.0, -100.0), Vec2::new(300.0, 200.0));
+    let mut objects = random_objects(2000, &bounds, 5);
+    for (i, object) in objects.iter_mut().enumerate() {
+        object.set_radius([1.5, 3.0, 6.0][i % 3]);
+    }
+    // Stragglers far away from everything else
+    objects.push(VerletObject::new(Vec2::new(1.0e6, -1.0e6)));
+    objects.push(VerletObject::new(Vec2::new(1.0e6 + 2.0, -1.0e6)));
+
+    let expected = brute_force_contacts(&objects);
+    assert!(expected.contains(&(2000, 2001)));
+    assert_eq!(spatial_hash_contacts(&objects), expected);
+}
@@ -0,0 +1,61 @@
+use verlet::{Solver, Vec2, VerletObject, WorldBounds};
+
+const STEPS: u32 = 120;
+const DT: f32 = 1.0 / 60.0;
+const SUBSTEPS: u32 = 8;
+
+/// Hash of the state after `STEPS` steps of this scene, recorded from a
+/// deterministic run. Only update it for deliberate changes to the physics.
+const EXPECTED_HASH: u64 = 2337472651175720032;
+
+/// A block of objects dropped into a small box, with a little jitter so the
+/// pile does not stack perfectly.
+fn scene() -> Vec<VerletObject> {
+    (0..400)
+        .map(|i| {
+            let (col, row) = ((i % 20) as f32, (i / 20) as f32);
+            let jitter = ((i * 7919) % 13) as f32 * 0.05;
+            VerletObject::new(Vec2::new(20.0 + col * 7.0 + jitter, 20.0 + row * 7.0))
+        })
+        .collect()
+}
+
+/// FNV-1a over the raw bits of every position.
+fn hash_state(objects: &[VerletObject]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for object in objects {
+        let current = object.get_position();
+        let old = object.get_old_position();
+        for value in [current.x, current.y, old.x, old.y] {
+            for byte in value.to_bits().to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+    }
+    hash
+}
+
+fn run(threads: usize) -> u64 {
+    let mut solver = Solver::new();
+    solver.set_bounds(WorldBounds::from_size(Vec2::new(200.0, 200.0)));
+    solver.set_deterministic(true);
+    solver.set_thread_count(threads).unwrap();
+    let mut objects = scene();
+    for _ in 0..STEPS {
+        solver.update(&mut objects, DT, SUBSTEPS);
+    }
+    hash_state(&objects)
+}
+
+#[test]
+fn deterministic_mode_is_independent_of_thread_count() {
+    let hashes: Vec<u64> = [1, 2, 4].into_iter().map(run).collect();
+    assert_eq!(hashes[0], hashes[1]);
+    assert_eq!(hashes[0], hashes[2]);
+}
+
+#[test]
+fn deterministic_mode_matches_recorded_state() {
+    assert_eq!(run(4), EXPECTED_HASH);
+}
@@ -0,0 +1,77 @@
+use verlet::{Rope, Solver, Vec2, VerletObject};
+
+#[test]
+fn points_are_spread_evenly_along_the_path() {
+    let path = vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(30.0, 0.0),
+        Vec2::new(30.0, 30.0),
+    ];
+    let points = Rope::along(path, 6).points();
+    assert_eq!(points.len(), 7);
+    for pair in points.windows(2) {
+        // Straight pieces are 10 long, the one round the corner is cut short
+        let distance = pair[0].distance(pair[1]);
+        assert!((distance - 10.0).abs() < 1e-4 || (distance - 50f32.sqrt()).abs() < 1e-4);
+    }
+    assert_eq!(points[3], Vec2::new(30.0, 0.0));
+    assert_eq!(points[6], Vec2::new(30.0, 30.0));
+}
+
+#[test]
+fn build_links_and_pins_the_rope() {
+    let mut solver = Solver::new();
+    let mut objects = vec![VerletObject::new(Vec2::new(10.0, 10.0))];
+    let links = Rope::new(Vec2::new(100.0, 100.0), Vec2::new(300.0, 100.0), 10)
+        .with_segment_length(25.0)
+        .with_stiffness(0.5)
+        .pinned(true, true)
+        .build(&mut solver, &mut objects);
+
+    assert_eq!(links, 1..12);
+    assert_eq!(solver.distance_constraints().len(), 10);
+    for (i, constraint) in solver.distance_constraints().iter().enumerate() {
+        assert_eq!((constraint.a, constraint.b), (1 + i, 2 + i));
+        assert_eq!(constraint.rest_length, 25.0);
+        assert_eq!(constraint.stiffness, 0.5);
+    }
+    assert!(objects[1].is_pinned() && objects[11].is_pinned());
+    assert!(!objects[6].is_pinned());
+    // Links share a group of their own
+    let group = objects[1].get_collision_group();
+    assert_ne!(group, 0);
+    assert!(objects[links]
+        .iter()
+        .all(|o| o.get_collision_group() == group));
+}
+
+#[test]
+fn folded_rope_only_pushes_apart_with_self_collision() {
+    // Folded back on itself, the two strands 2 apart between objects 6 wide
+    let closest = |self_collision: bool| {
+        let mut solver = Solver::new();
+        solver.set_gravity(Vec2::ZERO);
+        let mut objects = Vec::new();
+        let path = vec![
+            Vec2::new(300.0, 300.0),
+            Vec2::new(360.0, 300.0),
+            Vec2::new(360.0, 302.0),
+            Vec2::new(300.0, 302.0),
+        ];
+        Rope::along(path, 20)
+            .with_self_collision(self_collision)
+            .build(&mut solver, &mut objects);
+        for _ in 0..30 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+        }
+        let mut closest = f32::MAX;
+        for (i, a) in objects.iter().enumerate() {
+            for b in objects.iter().skip(i + 2) {
+                closest = closest.min(a.get_position().distance(b.get_position()));
+            }
+        }
+        closest
+    };
+    assert!(closest(false) < 3.0);
+    assert!(closest(true) > 5.5);
+}
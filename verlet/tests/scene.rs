@@ -0,0 +1,104 @@
+use verlet::{
+    Container, DistanceConstraint, Material, Scene, SceneError, Solver, Vec2, VerletObject, Wall,
+    WallRestitution, WorldBounds,
+};
+
+fn example() -> (Solver, Vec<VerletObject>) {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::new(0.0, 500.0));
+    solver.set_substeps(4);
+    solver.set_damping(0.5);
+    solver.set_drag(0.01);
+    solver.set_wall_material(Material::new(0.8, 0.5));
+    let mut restitution = WallRestitution::uniform(0.9);
+    restitution.set(Wall::Top, None);
+    solver.set_wall_restitution(restitution);
+    solver.set_bounds(WorldBounds::new(
+        Vec2::new(-10.0, 0.0),
+        Vec2::new(300.0, 200.0),
+    ));
+    solver.set_container(Container::Circle {
+        center: Vec2::new(100.0, 100.0),
+        radius: 80.0,
+    });
+    let mut objects = vec![
+        VerletObject::new(Vec2::new(100.0, 50.0)).with_radius(5.0),
+        VerletObject::new(Vec2::new(120.0, 50.0))
+            .with_mass(4.0)
+            .with_damping(2.0)
+            .with_collision_group(3)
+            .with_material(Material::new(0.3, 0.2).with_restitution(0.5)),
+    ];
+    objects[0].pin();
+    solver.add_distance_constraint(
+        DistanceConstraint::between(&objects, 0, 1, 0.5).with_max_force(1e6),
+    );
+    // Give the objects some velocity
+    solver.update(&mut objects, 1.0 / 60.0, 4);
+    (solver, objects)
+}
+
+#[test]
+fn scene_round_trips_through_a_file() {
+    let (solver, objects) = example();
+    // Unique per process, so concurrent runs do not share the file
+    let path = std::env::temp_dir().join(format!(
+        "verlet_scene_round_trip_{}.ron",
+        std::process::id()
+    ));
+    solver.save_scene(&objects, &path).unwrap();
+
+    let mut loaded_solver = Solver::new();
+    let loaded = loaded_solver.load_scene(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded_solver.gravity(), solver.gravity());
+    assert_eq!(loaded_solver.damping(), solver.damping());
+    assert_eq!(loaded_solver.drag(), solver.drag());
+    assert_eq!(loaded_solver.substeps(), solver.substeps());
+    assert_eq!(loaded_solver.bounds(), solver.bounds());
+    assert_eq!(loaded_solver.container(), solver.container());
+    assert_eq!(loaded_solver.wall_material(), solver.wall_material());
+    assert_eq!(loaded_solver.wall_restitution(), solver.wall_restitution());
+    assert_eq!(
+        loaded_solver.distance_constraints(),
+        solver.distance_constraints()
+    );
+    assert_eq!(loaded.len(), objects.len());
+    for (loaded, original) in loaded.iter().zip(&objects) {
+        assert_eq!(loaded.get_position(), original.get_position());
+        assert_eq!(loaded.get_old_position(), original.get_old_position());
+        assert_eq!(loaded.get_radius(), original.get_radius());
+        assert_eq!(loaded.get_inverse_mass(), original.get_inverse_mass());
+        assert_eq!(loaded.get_damping(), original.get_damping());
+        assert_eq!(loaded.get_material(), original.get_material());
+        assert_eq!(loaded.get_collision_group(), original.get_collision_group());
+        assert_eq!(loaded.is_pinned(), original.is_pinned());
+    }
+}
+
+#[test]
+fn constraints_past_the_particles_are_rejected() {
+    let (solver, objects) = example();
+    let mut scene = solver.to_scene(&objects);
+    scene
+        .distance_constraints
+        .push(DistanceConstraint::new(1, 2, 10.0, 1.0));
+    let text = scene.to_ron().unwrap();
+    assert!(matches!(
+        Scene::from_ron(&text),
+        Err(SceneError::InvalidConstraint(1))
+    ));
+}
+
+#[test]
+fn newer_scene_versions_are_rejected() {
+    let (solver, objects) = example();
+    let mut scene = solver.to_scene(&objects);
+    scene.version += 1;
+    let text = scene.to_ron().unwrap();
+    assert!(matches!(
+        Scene::from_ron(&text),
+        Err(SceneError::UnsupportedVersion(_))
+    ));
+}
@@ -0,0 +1,125 @@
+use verlet::{
+    Container, DistanceConstraint, Material, Snapshot, SnapshotError, Solver, Vec2, VerletObject,
+    Wall, WallRestitution, WorldBounds,
+};
+
+fn example() -> (Solver, Vec<VerletObject>) {
+    let mut solver = Solver::new();
+    solver.set_bounds(WorldBounds::from_size(Vec2::new(200.0, 200.0)));
+    solver.set_container(Container::InvertedCircle {
+        center: Vec2::new(100.0, 150.0),
+        radius: 30.0,
+    });
+    solver.set_deterministic(true);
+    solver.set_damping(0.25);
+    solver.set_drag(0.002);
+    solver.set_wall_material(Material::new(0.5, 0.4));
+    let mut restitution = WallRestitution::uniform(0.3);
+    restitution.set(Wall::Circle, None);
+    solver.set_wall_restitution(restitution);
+    let mut objects: Vec<_> = (0..300)
+        .map(|i| {
+            let position = Vec2::new(10.0 + (i % 30) as f32 * 6.0, 10.0 + (i / 30) as f32 * 6.0);
+            VerletObject::new(position)
+                .with_radius(2.0 + (i % 3) as f32)
+                .with_damping((i % 2) as f32)
+                .with_collision_group(i % 5)
+                .with_material(Material {
+                    static_friction: 0.1 * (i % 4) as f32,
+                    kinetic_friction: 0.05,
+                    restitution: (i % 3 == 0).then_some(0.7),
+                })
+        })
+        .collect();
+    objects[0].pin();
+    solver.add_distance_constraint(
+        DistanceConstraint::between(&objects, 0, 1, 1.0).with_max_strain(0.5),
+    );
+    (solver, objects)
+}
+
+fn assert_same_objects(a: &[VerletObject], b: &[VerletObject]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert_eq!(a.get_position(), b.get_position());
+        assert_eq!(a.get_old_position(), b.get_old_position());
+        assert_eq!(a.get_radius(), b.get_radius());
+        assert_eq!(a.get_inverse_mass(), b.get_inverse_mass());
+        assert_eq!(a.get_damping(), b.get_damping());
+        assert_eq!(a.get_material(), b.get_material());
+        assert_eq!(a.get_collision_group(), b.get_collision_group());
+        assert_eq!(a.is_pinned(), b.is_pinned());
+    }
+}
+
+#[test]
+fn snapshot_round_trips() {
+    let (mut solver, mut objects) = example();
+    solver.run_to_step(&mut objects, 1.0 / 60.0, 30);
+
+    let snapshot = Snapshot::capture(&solver, &objects);
+    assert_eq!(snapshot.step, 30);
+    let decoded = Snapshot::decode(&snapshot.encode()).unwrap();
+
+    assert_eq!(decoded.step, snapshot.step);
+    assert_eq!(decoded.scene.gravity, snapshot.scene.gravity);
+    assert_eq!(decoded.scene.damping, snapshot.scene.damping);
+    assert_eq!(decoded.scene.drag, snapshot.scene.drag);
+    assert_eq!(decoded.scene.substeps, snapshot.scene.substeps);
+    assert_eq!(decoded.scene.bounds, snapshot.scene.bounds);
+    assert_eq!(decoded.scene.container, snapshot.scene.container);
+    assert_eq!(decoded.scene.wall_material, snapshot.scene.wall_material);
+    assert_eq!(
+        decoded.scene.wall_restitution,
+        snapshot.scene.wall_restitution
+    );
+    assert_eq!(
+        decoded.scene.distance_constraints,
+        snapshot.scene.distance_constraints
+    );
+    assert_same_objects(&decoded.scene.particles, &snapshot.scene.particles);
+}
+
+#[test]
+fn run_resumed_from_a_snapshot_matches_uninterrupted_run() {
+    let (mut solver, mut objects) = example();
+    solver.run_to_step(&mut objects, 1.0 / 60.0, 20);
+    // Unique per process, so concurrent runs do not share the file
+    let path =
+        std::env::temp_dir().join(format!("verlet_snapshot_resume_{}.bin", std::process::id()));
+    solver.save_snapshot(&objects, &path).unwrap();
+    solver.run_to_step(&mut objects, 1.0 / 60.0, 40);
+
+    let mut resumed_solver = Solver::new();
+    resumed_solver.set_deterministic(true);
+    let mut resumed = resumed_solver.load_snapshot(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(resumed_solver.step_count(), 20);
+    resumed_solver.run_to_step(&mut resumed, 1.0 / 60.0, 40);
+
+    assert_same_objects(&resumed, &objects);
+}
+
+#[test]
+fn corrupt_snapshots_are_rejected() {
+    let (solver, objects) = example();
+    let bytes = Snapshot::capture(&solver, &objects).encode();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(
+        Snapshot::decode(&bad_magic),
+        Err(SnapshotError::BadMagic)
+    ));
+    assert!(matches!(
+        Snapshot::decode(&bytes[..bytes.len() - 1]),
+        Err(SnapshotError::Truncated)
+    ));
+
+    let mut snapshot = Snapshot::capture(&solver, &objects);
+    snapshot.scene.distance_constraints[0].b = objects.len();
+    assert!(matches!(
+        Snapshot::decode(&snapshot.encode()),
+        Err(SnapshotError::InvalidConstraint(0))
+    ));
+}
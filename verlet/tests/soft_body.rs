@@ -0,0 +1,78 @@
+use verlet::{SoftBody, SoftBodyHandle, Solver, Vec2, VerletObject, WorldBounds};
+
+/// A round body of radius 40 falling onto the floor of the default 800x600 box.
+fn falling_body(pressure: f32) -> (Solver, Vec<VerletObject>, SoftBodyHandle) {
+    let mut solver = Solver::new();
+    let mut objects = Vec::new();
+    let body = SoftBody::circle(Vec2::new(400.0, 400.0), 40.0, 24)
+        .with_pressure(pressure)
+        .build(&mut solver, &mut objects);
+    (solver, objects, body)
+}
+
+#[test]
+fn build_closes_the_loop() {
+    let (solver, objects, body) = falling_body(2000.0);
+    assert_eq!(body.indices(), 0..24);
+    assert_eq!(objects.len(), 24);
+    assert_eq!(solver.distance_constraints().len(), 24);
+    assert_eq!(solver.force_generator_count(), 1);
+    let last = solver.distance_constraints().last().unwrap();
+    assert_eq!((last.a, last.b), (23, 0));
+    assert!(objects
+        .iter()
+        .all(|object| object.get_collision_group() == objects[0].get_collision_group()));
+    // A 24-gon is a little smaller than its circle
+    let circle = std::f32::consts::PI * 40.0 * 40.0;
+    assert!((body.area(&objects) - circle).abs() < circle * 0.02);
+}
+
+#[test]
+fn pressure_keeps_the_body_inflated() {
+    let (mut solver, mut objects, body) = falling_body(2000.0);
+    let target = body.area(&objects);
+    for _ in 0..180 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert!(body.area(&objects) > target * 0.8);
+
+    // An empty loop is just a rope and falls flat
+    let (mut solver, mut objects, body) = falling_body(0.0);
+    for _ in 0..180 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert!(body.area(&objects) < target * 0.5);
+}
+
+#[test]
+fn softer_bodies_squash_more() {
+    let squashed_area = |pressure: f32| {
+        let (mut solver, mut objects, body) = falling_body(pressure);
+        for _ in 0..180 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+        }
+        body.area(&objects)
+    };
+    assert!(squashed_area(500.0) < squashed_area(5000.0));
+}
+
+#[test]
+fn bodies_collide_with_other_objects() {
+    let (mut solver, mut objects, body) = falling_body(2000.0);
+    // A shaft just wide enough for the body, so the ball cannot roll off it
+    solver.set_bounds(WorldBounds::new(
+        Vec2::new(355.0, 0.0),
+        Vec2::new(90.0, 600.0),
+    ));
+    let ball = objects.len();
+    objects.push(VerletObject::new(Vec2::new(400.0, 300.0)).with_radius(10.0));
+    for _ in 0..180 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    // It rests on the body rather than sinking through to the floor
+    let top = body
+        .indices()
+        .map(|index| objects[index].get_position().y)
+        .fold(f32::INFINITY, f32::min);
+    assert!(objects[ball].get_position().y < top);
+}
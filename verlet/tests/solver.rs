@@ -0,0 +1,574 @@
+use verlet::collision::{BruteForce, SpatialHash};
+use verlet::force::{Attractor, Explosion, LinearDrag, Vortex, Wind};
+use verlet::{
+    ConstraintBroken, Container, DistanceConstraint, FixedTimestep, Material, Solver, Vec2,
+    VerletObject, WallRestitution, WorldBounds,
+};
+
+#[test]
+fn pinned_object_is_not_moved() {
+    let mut solver = Solver::new();
+    let anchor = Vec2::new(400.0, 300.0);
+    let mut objects = vec![
+        VerletObject::new(anchor),
+        VerletObject::new(anchor - Vec2::new(0.0, 20.0)),
+    ];
+    objects[0].pin();
+    for _ in 0..60 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    // The second object fell onto the pinned one and rests on top of it
+    assert_eq!(objects[0].get_position(), anchor);
+    assert!(objects[1].get_position().y < anchor.y);
+
+    objects[0].unpin();
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert!(objects[0].get_position().y > anchor.y);
+}
+
+#[test]
+fn distance_constraint_holds_a_hanging_object() {
+    let mut solver = Solver::new();
+    let anchor = Vec2::new(400.0, 100.0);
+    let mut objects = vec![
+        VerletObject::new(anchor),
+        VerletObject::new(anchor + Vec2::new(50.0, 0.0)),
+    ];
+    objects[0].pin();
+    solver.add_distance_constraint(DistanceConstraint::between(&objects, 0, 1, 1.0));
+    for _ in 0..120 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    // Swung down like a pendulum without stretching the stick
+    let length = objects[0]
+        .get_position()
+        .distance(objects[1].get_position());
+    assert!((length - 50.0).abs() < 0.5, "length {length}");
+}
+
+#[test]
+fn containers_keep_objects_on_the_right_side() {
+    let center = Vec2::new(400.0, 300.0);
+    let mut solver = Solver::new();
+    solver.set_container(Container::Circle {
+        center,
+        radius: 100.0,
+    });
+    let mut objects: Vec<_> = (0..20)
+        .map(|i| VerletObject::new(center + Vec2::new(i as f32 * 4.0 - 40.0, -50.0)))
+        .collect();
+    for _ in 0..120 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    for object in &objects {
+        let distance = object.get_position().distance(center);
+        assert!(distance <= 100.0 - object.get_radius() + 0.01);
+    }
+
+    solver.set_container(Container::InvertedCircle {
+        center,
+        radius: 50.0,
+    });
+    for _ in 0..120 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    for object in &objects {
+        let distance = object.get_position().distance(center);
+        assert!(distance >= 50.0 + object.get_radius() - 0.01);
+    }
+}
+
+#[test]
+fn inverted_circle_near_an_edge_keeps_objects_in_bounds() {
+    let bounds = WorldBounds::from_size(Vec2::new(800.0, 600.0));
+    // The obstacle pokes out of the floor
+    let container = Container::InvertedCircle {
+        center: Vec2::new(400.0, 580.0),
+        radius: 50.0,
+    };
+    for position in [
+        Vec2::new(400.0, 590.0),
+        Vec2::new(420.0, 599.0),
+        Vec2::new(400.0, 580.0),
+    ] {
+        let constrained = container.constrain(&bounds, position, 3.0);
+        assert!((4.0..=796.0).contains(&constrained.x));
+        assert!((4.0..=596.0).contains(&constrained.y), "{constrained:?}");
+    }
+}
+
+#[test]
+fn objects_caught_outside_a_shrink_are_moved_in_gently() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let mut objects = vec![
+        VerletObject::new(Vec2::new(700.0, 300.0)),
+        VerletObject::new(Vec2::new(400.0, 550.0)),
+    ];
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+
+    solver.set_bounds(WorldBounds::from_size(Vec2::new(400.0, 300.0)));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    for object in &objects {
+        let position = object.get_position();
+        assert!(position.x <= 396.0 && position.y <= 296.0);
+        // Not launched by the correction
+        assert!(object.get_velocity().length() < 1e-3);
+    }
+}
+
+#[test]
+fn fast_objects_stay_inside_the_walls() {
+    let mut solver = Solver::new();
+    // Falls more than its radius per substep
+    let mut objects = vec![VerletObject::new(Vec2::new(400.0, 10.0)).with_radius(1.0)];
+    for _ in 0..300 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+        assert!(objects[0].get_position().y < 600.0);
+    }
+    // Resting on the floor instead of pushing into it
+    assert!((objects[0].get_position().y - 598.0).abs() < 0.1);
+    assert!(objects[0].get_velocity().length() < 0.1);
+}
+
+#[test]
+fn advance_runs_whole_fixed_steps() {
+    let mut solver = Solver::new();
+    solver.set_fixed_timestep(FixedTimestep::new(0.01, 4));
+    let mut objects = vec![VerletObject::new(Vec2::new(400.0, 100.0))];
+
+    let report = solver.advance(&mut objects, 0.025);
+    assert_eq!(report.steps, 2);
+    assert!((report.alpha - 0.5).abs() < 1e-3);
+
+    // The leftover half step is carried into the next frame
+    let report = solver.advance(&mut objects, 0.005);
+    assert_eq!(report.steps, 1);
+    assert!(report.alpha < 1e-3);
+
+    // A hitch is capped instead of being caught up on
+    let report = solver.advance(&mut objects, 1.0);
+    assert_eq!(report.steps, 4);
+    assert!((0.0..1.0).contains(&report.alpha));
+    let report = solver.advance(&mut objects, 0.0);
+    assert_eq!(report.steps, 0);
+}
+
+#[test]
+#[should_panic(expected = "fixed timestep must be greater than 0")]
+fn zero_fixed_timestep_is_rejected() {
+    FixedTimestep::new(0.0, 4);
+}
+
+#[test]
+fn interpolation_blends_over_the_whole_step() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    solver.set_fixed_timestep(FixedTimestep::new(0.01, 4));
+    // 1 unit per substep, 8 per step
+    let mut object = VerletObject::new(Vec2::new(100.0, 300.0));
+    object.set_velocity(Vec2::new(1.0, 0.0));
+    let mut objects = vec![object];
+
+    let report = solver.advance(&mut objects, 0.015);
+    assert_eq!(report.steps, 1);
+    assert!((objects[0].get_position().x - 108.0).abs() < 1e-3);
+    let drawn = objects[0].interpolated_position(report.alpha);
+    assert!((drawn.x - 104.0).abs() < 1e-2);
+
+    // Drawn where the last step started, even right after a bounce
+    let mut objects = vec![VerletObject::new(Vec2::new(790.0, 300.0))];
+    objects[0].set_velocity(Vec2::new(3.0, 0.0));
+    solver.set_wall_restitution(WallRestitution::uniform(1.0));
+    let report = solver.advance(&mut objects, 0.01);
+    assert_eq!(report.steps, 1);
+    assert_eq!(
+        objects[0].interpolated_position(0.0),
+        Vec2::new(790.0, 300.0)
+    );
+}
+
+#[test]
+fn every_broadphase_settles_the_same_pile() {
+    let heights: Vec<f32> = (0..3)
+        .map(|broadphase| {
+            let mut solver = Solver::new();
+            solver.set_bounds(WorldBounds::from_size(Vec2::new(100.0, 100.0)));
+            match broadphase {
+                0 => solver.set_broadphase(BruteForce::new()),
+                1 => {} // dense grid is the default
+                _ => solver.set_broadphase(SpatialHash::new()),
+            }
+            let mut objects: Vec<_> = (0..100)
+                .map(|i| {
+                    VerletObject::new(Vec2::new(
+                        10.0 + (i % 10) as f32 * 8.0,
+                        10.0 + (i / 10) as f32 * 8.0,
+                    ))
+                })
+                .collect();
+            for _ in 0..120 {
+                solver.update(&mut objects, 1.0 / 60.0, 8);
+            }
+            objects
+                .iter()
+                .map(|object| object.get_position().y)
+                .sum::<f32>()
+                / objects.len() as f32
+        })
+        .collect();
+    assert!((heights[0] - heights[1]).abs() < 1.0, "{heights:?}");
+    assert!((heights[0] - heights[2]).abs() < 1.0, "{heights:?}");
+}
+
+#[test]
+fn released_object_keeps_drag_velocity() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let mut objects = vec![VerletObject::new(Vec2::new(100.0, 300.0))];
+    solver.grab(&objects, 0);
+    assert_eq!(solver.grabbed(), Some(0));
+    for step in 1..=10 {
+        solver.set_grab_target(Vec2::new(100.0 + 8.0 * step as f32, 300.0));
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    solver.release();
+
+    // Dragged 8 units per step, i.e. 1 unit per substep
+    let velocity = objects[0].get_velocity();
+    assert!(
+        (velocity - Vec2::new(1.0, 0.0)).length() < 1e-3,
+        "{velocity}"
+    );
+    let before = objects[0].get_position();
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert!((objects[0].get_position().x - before.x - 8.0).abs() < 1e-2);
+}
+
+#[test]
+fn force_generators_can_be_added_and_removed() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let center = Vec2::new(400.0, 300.0);
+    let mut objects = vec![VerletObject::new(center + Vec2::new(100.0, 0.0))];
+
+    let id = solver.add_force_generator(Attractor::new(center, 200.0, 1000.0));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert!(objects[0].get_velocity().x < 0.0);
+
+    assert!(solver.remove_force_generator(id));
+    assert!(!solver.remove_force_generator(id));
+    let velocity = objects[0].get_velocity();
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert!((objects[0].get_velocity() - velocity).length() < 1e-4);
+}
+
+#[test]
+fn explosion_pushes_outwards_then_finishes() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let center = Vec2::new(400.0, 300.0);
+    let mut objects = vec![
+        VerletObject::new(center + Vec2::new(-50.0, 0.0)),
+        VerletObject::new(center + Vec2::new(50.0, 0.0)),
+    ];
+    solver.add_force_generator(Explosion::new(center, 200.0, 10000.0, 0.1));
+    for _ in 0..10 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert!(objects[0].get_velocity().x < 0.0);
+    assert!(objects[1].get_velocity().x > 0.0);
+    assert_eq!(solver.force_generator_count(), 0);
+}
+
+#[test]
+fn vortex_swirls_counter_clockwise() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let center = Vec2::new(400.0, 300.0);
+    let mut objects = vec![
+        // Right of the centre, and out of reach below it
+        VerletObject::new(center + Vec2::new(100.0, 0.0)),
+        VerletObject::new(center + Vec2::new(0.0, 250.0)),
+    ];
+    solver.add_force_generator(Vortex::new(center, 200.0, 1000.0));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    // Up the screen, along the circle
+    let velocity = objects[0].get_velocity();
+    assert!(velocity.y < 0.0);
+    assert!(velocity.x.abs() < velocity.y.abs() * 0.1);
+    assert_eq!(objects[1].get_velocity(), Vec2::ZERO);
+}
+
+#[test]
+fn linear_drag_slows_light_objects_faster() {
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let mut objects = vec![
+        VerletObject::new(Vec2::new(100.0, 200.0)),
+        VerletObject::new(Vec2::new(100.0, 400.0)).with_mass(2.0),
+    ];
+    for object in &mut objects {
+        object.set_velocity(Vec2::new(0.5, 0.0));
+    }
+    solver.add_force_generator(LinearDrag::new(2.0));
+    for _ in 0..60 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    // Decays like exp(-coefficient / mass * t)
+    let light = objects[0].get_velocity().x / 0.5;
+    let heavy = objects[1].get_velocity().x / 0.5;
+    assert!((light - (-2.0f32).exp()).abs() < 0.02);
+    assert!((heavy - (-1.0f32).exp()).abs() < 0.02);
+}
+
+#[test]
+fn wind_gusts_are_bounded_and_repeatable() {
+    let wind = Wind::new(Vec2::new(100.0, 0.0), 5.0).with_turbulence(0.5, 200.0, 7);
+    let mut differs = false;
+    for i in 0..100 {
+        let position = Vec2::new(i as f32 * 37.0, i as f32 * 11.0);
+        let time = i as f64 * 0.13;
+        let sample = wind.sample(position, time);
+        assert!((sample - wind.velocity).length() <= 100.0 * 0.5 * 2f32.sqrt() + 1e-3);
+        assert_eq!(sample, wind.sample(position, time));
+        let other_seed = Wind { seed: 8, ..wind };
+        differs |= other_seed.sample(position, time) != sample;
+    }
+    assert!(differs);
+
+    // Steady wind carries a resting object up to its speed
+    let mut solver = Solver::new();
+    solver.set_gravity(Vec2::ZERO);
+    let mut objects = vec![VerletObject::new(Vec2::new(100.0, 300.0))];
+    solver
+        .add_force_generator(Wind::new(Vec2::new(100.0, 0.0), 5.0).with_turbulence(0.0, 200.0, 0));
+    for _ in 0..120 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    let speed = objects[0].get_velocity().x * 60.0 * 8.0;
+    assert!((speed - 100.0).abs() < 1.0);
+}
+
+#[test]
+fn damping_does_not_depend_on_substeps() {
+    let run = |substeps| {
+        let mut solver = Solver::new();
+        solver.set_gravity(Vec2::ZERO);
+        solver.set_damping(1.0);
+        let mut objects = vec![VerletObject::new(Vec2::new(100.0, 300.0))];
+        // 600 units per second
+        objects[0].set_velocity(Vec2::new(600.0 / 60.0 / substeps as f32, 0.0));
+        for _ in 0..60 {
+            solver.update(&mut objects, 1.0 / 60.0, substeps);
+        }
+        objects[0].get_velocity().x * (60 * substeps) as f32
+    };
+    // One second at a rate of 1 leaves 1/e of the speed
+    let expected = 600.0 * (-1.0f32).exp();
+    for substeps in [1, 4, 16] {
+        let speed = run(substeps);
+        assert!((speed - expected).abs() < 1.0, "{substeps}: {speed}");
+    }
+}
+
+#[test]
+fn drag_gives_a_terminal_speed() {
+    let mut solver = Solver::new();
+    solver.set_broadphase(BruteForce::new());
+    solver.set_bounds(WorldBounds::new(Vec2::ZERO, Vec2::new(1000.0, 100_000.0)));
+    solver.set_drag(0.1);
+    let mut objects = vec![
+        VerletObject::new(Vec2::new(100.0, 10.0)),
+        VerletObject::new(Vec2::new(200.0, 10.0)).with_mass(4.0),
+    ];
+    for _ in 0..180 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    // sqrt(g * m / drag): 100 and 200 units per second, give or take the
+    // integration error
+    let speed = |object: &VerletObject| object.get_velocity().y * 60.0 * 8.0;
+    assert!((speed(&objects[0]) - 100.0).abs() < 2.0);
+    assert!((speed(&objects[1]) - 200.0).abs() < 4.0);
+}
+
+#[test]
+fn wall_friction_stops_a_sliding_object() {
+    let slide = |material: Material| {
+        let mut solver = Solver::new();
+        solver.set_wall_material(material);
+        let mut objects = vec![VerletObject::new(Vec2::new(100.0, 596.0)).with_material(material)];
+        for _ in 0..10 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+        }
+        // Resting on the floor, then pushed sideways at 120 units per second
+        objects[0].set_velocity(Vec2::new(120.0 / 60.0 / 8.0, 0.0));
+        for _ in 0..120 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+        }
+        objects[0].get_position().x - 100.0
+    };
+    let frictionless = slide(Material::FRICTIONLESS);
+    let rough = slide(Material::new(0.6, 0.5));
+    assert!((frictionless - 240.0).abs() < 1.0, "{frictionless}");
+    // Decelerates at kinetic friction * g, i.e. stops after 120² / (2 * 500) units
+    assert!((rough - 14.4).abs() < 2.0, "{rough}");
+}
+
+/// A pinned anchor with a ball of `mass` hanging `length` below it.
+fn hanging_ball(
+    mass: f32,
+    length: f32,
+    constraint: impl FnOnce(DistanceConstraint) -> DistanceConstraint,
+) -> (Solver, Vec<VerletObject>) {
+    let mut solver = Solver::new();
+    let anchor = Vec2::new(400.0, 100.0);
+    let mut objects = vec![
+        VerletObject::new(anchor),
+        VerletObject::new(anchor + Vec2::new(0.0, length)).with_mass(mass),
+    ];
+    objects[0].pin();
+    solver.add_distance_constraint(constraint(DistanceConstraint::between(&objects, 0, 1, 1.0)));
+    (solver, objects)
+}
+
+#[test]
+fn constraint_breaks_past_its_max_strain() {
+    let (mut solver, mut objects) = hanging_ball(1.0, 50.0, |link| link.with_max_strain(0.5));
+    for _ in 0..30 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert_eq!(solver.distance_constraints().len(), 1);
+    assert_eq!(solver.drain_broken_constraints().count(), 0);
+
+    // Drag the ball to twice the length, then to half of it
+    solver.grab(&objects, 1);
+    solver.set_grab_target(Vec2::new(400.0, 125.0));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert_eq!(solver.distance_constraints().len(), 1);
+    solver.set_grab_target(Vec2::new(400.0, 200.0));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert!(solver.distance_constraints().is_empty());
+    let broken: Vec<_> = solver.drain_broken_constraints().collect();
+    assert_eq!(
+        broken,
+        [ConstraintBroken {
+            a: 0,
+            b: 1,
+            step: solver.step_count(),
+        }]
+    );
+    assert_eq!(solver.drain_broken_constraints().count(), 0);
+}
+
+#[test]
+fn constraint_breaks_past_its_max_force() {
+    // The link pulls with the ball's weight, 1000 per unit of mass
+    let (mut solver, mut objects) = hanging_ball(2.0, 50.0, |link| link.with_max_force(2500.0));
+    for _ in 0..60 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert_eq!(solver.distance_constraints().len(), 1);
+
+    let (mut solver, mut objects) = hanging_ball(3.0, 50.0, |link| link.with_max_force(2500.0));
+    solver.update(&mut objects, 1.0 / 60.0, 8);
+    assert!(solver.distance_constraints().is_empty());
+    assert_eq!(solver.drain_broken_constraints().count(), 1);
+    // Nothing holds the ball any more
+    for _ in 0..20 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert!(objects[1].get_position().y > 200.0);
+}
+
+#[test]
+fn constraints_without_limits_never_break() {
+    let (mut solver, mut objects) = hanging_ball(1000.0, 50.0, |link| link);
+    solver.grab(&objects, 1);
+    solver.set_grab_target(Vec2::new(700.0, 500.0));
+    for _ in 0..10 {
+        solver.update(&mut objects, 1.0 / 60.0, 8);
+    }
+    assert_eq!(solver.distance_constraints().len(), 1);
+    assert_eq!(solver.drain_broken_constraints().count(), 0);
+}
+
+#[test]
+fn friction_holds_up_a_stack() {
+    // Two objects side by side on the floor with a third on top
+    let top_height = |material: Material| {
+        let mut solver = Solver::new();
+        solver.set_wall_material(material);
+        let floor = 596.0;
+        let mut objects: Vec<_> = [
+            Vec2::new(397.0, floor),
+            Vec2::new(403.0, floor),
+            Vec2::new(400.0, floor - 6.0 * 0.866),
+        ]
+        .into_iter()
+        .map(|position| VerletObject::new(position).with_material(material))
+        .collect();
+        for _ in 0..120 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+        }
+        floor - objects[2].get_position().y
+    };
+    // Without friction the bottom two slide apart and the top one drops to the floor
+    assert!(top_height(Material::FRICTIONLESS) < 1.0);
+    assert!(top_height(Material::new(0.6, 0.5)) > 4.5);
+}
+
+#[test]
+fn wall_restitution_sets_the_bounce_height() {
+    // Dropped from `height` onto the floor, highest point after the first bounce
+    let rebound = |radius: f32, height: f32, restitution: f32| {
+        let mut solver = Solver::new();
+        solver.set_wall_restitution(WallRestitution::uniform(restitution));
+        let floor = 600.0 - radius - 1.0;
+        let mut objects =
+            vec![VerletObject::new(Vec2::new(400.0, floor - height)).with_radius(radius)];
+        let mut bounced = false;
+        let mut highest = floor;
+        for _ in 0..240 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+            // Moving up for the first time
+            bounced |= objects[0].get_velocity().y < 0.0;
+            if bounced {
+                highest = highest.min(objects[0].get_position().y);
+            }
+        }
+        floor - highest
+    };
+    assert!((rebound(3.0, 296.0, 1.0) - 296.0).abs() < 5.0);
+    assert!((rebound(3.0, 296.0, 0.5) - 296.0 * 0.25).abs() < 5.0);
+    assert!(rebound(3.0, 296.0, 0.0) < 0.5);
+
+    // Hitting the floor faster than one radius per substep
+    assert!((rebound(1.0, 588.0, 1.0) - 588.0).abs() < 10.0);
+    assert!((rebound(1.0, 588.0, 0.5) - 588.0 * 0.25).abs() < 10.0);
+    assert!(rebound(1.0, 588.0, 0.0) < 0.5);
+}
+
+#[test]
+fn restitution_between_objects() {
+    // Head on at 1 unit per substep each way
+    let collide = |restitution: f32| {
+        let mut solver = Solver::new();
+        solver.set_gravity(Vec2::ZERO);
+        let material = Material::FRICTIONLESS.with_restitution(restitution);
+        let mut objects = vec![
+            VerletObject::new(Vec2::new(380.0, 300.0)).with_material(material),
+            VerletObject::new(Vec2::new(420.0, 300.0)).with_material(material),
+        ];
+        objects[0].set_velocity(Vec2::new(1.0, 0.0));
+        objects[1].set_velocity(Vec2::new(-1.0, 0.0));
+        for _ in 0..6 {
+            solver.update(&mut objects, 1.0 / 60.0, 8);
+        }
+        (objects[0].get_velocity().x, objects[1].get_velocity().x)
+    };
+    let (a, b) = collide(1.0);
+    assert!((a + 1.0).abs() < 1e-3 && (b - 1.0).abs() < 1e-3, "{a} {b}");
+    let (a, b) = collide(0.0);
+    assert!(a.abs() < 1e-3 && b.abs() < 1e-3, "{a} {b}");
+}